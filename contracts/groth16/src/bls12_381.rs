@@ -0,0 +1,626 @@
+//! The BLS12-381 backend, on the Soroban BLS12-381 host functions, and the
+//! extensions only it supports: arkworks-serialized keys, gnark Pedersen
+//! commitments, the key registry encoding and batch verification.
+
+use soroban_sdk::{
+    crypto::bls12_381::{Bls12_381, Fr, G1Affine, G2Affine},
+    Bytes, BytesN, Env, Vec, U256,
+};
+
+use crate::{curve, errors::Groth16Error, groth16, pairing::PairingBackend};
+
+pub type Proof = groth16::Proof<Bls12_381>;
+pub type VerifyingKey = groth16::VerifyingKey<Bls12_381>;
+pub type PreparedVerifyingKey = groth16::PreparedVerifyingKey<Bls12_381>;
+
+/// BLS12-381 base field modulus p, big-endian.
+const FP_MODULUS: [u8; 48] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// BLS12-381 scalar field modulus r, big-endian.
+const FR_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// 2^256 mod r, big-endian.
+const TWO_POW_256_MOD_R: [u8; 32] = [
+    0x18, 0x24, 0xb1, 0x59, 0xac, 0xc5, 0x05, 0x6f, 0x99, 0x8c, 0x4f, 0xef, 0xec, 0xbc, 0x4f, 0xf5,
+    0x58, 0x84, 0xb7, 0xfa, 0x00, 0x03, 0x48, 0x02, 0x00, 0x00, 0x00, 0x01, 0xff, 0xff, 0xff, 0xfe,
+];
+
+/// Domain separation tag gnark hashes each commitment under
+/// (`constraint.CommitmentDst`).
+const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+
+/// Domain separation tag of the challenge gnark folds several commitment
+/// proofs of knowledge with.
+const COMMITMENT_CHALLENGE_DST: &[u8] = b"G16-BSB22";
+
+impl PairingBackend for Bls12_381 {
+    type G1 = G1Affine;
+    type G2 = G2Affine;
+    type Fr = Fr;
+
+    const G1_LEN: u32 = 96;
+    const G2_LEN: u32 = 192;
+    const COMPRESSED_LENS: Option<(u32, u32)> = Some((48, 96));
+
+    fn env(&self) -> &Env {
+        Bls12_381::env(self)
+    }
+
+    fn g1_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<G1Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG1Length)?;
+        g1_from_checked(env, &arr, allow_identity)
+    }
+
+    fn g2_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<G2Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG2Length)?;
+        g2_from_checked(env, &arr, allow_identity)
+    }
+
+    /// Decompresses a ZCash-encoded G1 point.
+    fn g1_from_compressed(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<G1Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG1Length)?;
+        let arr = curve::g1_decompress(&arr).ok_or(Groth16Error::DecompressingG1Failed)?;
+        g1_from_checked(env, &arr, allow_identity)
+    }
+
+    /// Decompresses a ZCash-encoded G2 point.
+    fn g2_from_compressed(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<G2Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG2Length)?;
+        let arr = curve::g2_decompress(&arr).ok_or(Groth16Error::DecompressingG2Failed)?;
+        g2_from_checked(env, &arr, allow_identity)
+    }
+
+    fn g1_to_bytes(p: &G1Affine) -> Bytes {
+        p.to_bytes().into()
+    }
+
+    fn g2_to_bytes(p: &G2Affine) -> Bytes {
+        p.to_bytes().into()
+    }
+
+    fn fr_from_bytes(bytes: BytesN<32>) -> Result<Fr, Groth16Error> {
+        fr_from_canonical_bytes(bytes)
+    }
+
+    fn g1_neg(p: &G1Affine) -> G1Affine {
+        negate_g1(p)
+    }
+
+    fn g2_neg(p: &G2Affine) -> G2Affine {
+        negate_g2(p)
+    }
+
+    fn g1_add(&self, p: &G1Affine, q: &G1Affine) -> G1Affine {
+        Bls12_381::g1_add(self, p, q)
+    }
+
+    fn g1_mul(&self, p: &G1Affine, scalar: &Fr) -> G1Affine {
+        Bls12_381::g1_mul(self, p, scalar)
+    }
+
+    fn g1_msm(&self, points: Vec<G1Affine>, scalars: Vec<Fr>) -> G1Affine {
+        Bls12_381::g1_msm(self, points, scalars)
+    }
+
+    fn pairing_check(&self, g1: Vec<G1Affine>, g2: Vec<G2Affine>) -> bool {
+        Bls12_381::pairing_check(self, g1, g2)
+    }
+}
+
+/// Parses a big-endian public input, rejecting values not below r.
+pub fn fr_from_canonical_bytes(bytes: BytesN<32>) -> Result<Fr, Groth16Error> {
+    if bytes.to_array() >= FR_MODULUS {
+        return Err(Groth16Error::PublicInputGreaterThenFieldSize);
+    }
+    Ok(Fr::from_bytes(bytes))
+}
+
+/// `expand_message_xmd` from RFC 9380 with SHA-256, for `len` up to
+/// 255 * 32 bytes and a `dst` of at most 255 bytes.
+pub fn expand_message_xmd(env: &Env, msg: &Bytes, dst: &[u8], len: u32) -> Bytes {
+    let mut dst_prime = Bytes::from_slice(env, dst);
+    dst_prime.push_back(dst.len() as u8);
+
+    let mut b0_input = Bytes::from_array(env, &[0u8; 64]);
+    b0_input.append(msg);
+    b0_input.extend_from_array(&(len as u16).to_be_bytes());
+    b0_input.push_back(0);
+    b0_input.append(&dst_prime);
+    let b0 = env.crypto().sha256(&b0_input).to_array();
+
+    // b_1 = H(b_0 || 1 || dst'), b_i = H((b_0 ^ b_{i-1}) || i || dst').
+    let mut out = Bytes::new(env);
+    let mut prev = [0u8; 32];
+    for i in 1..=len.div_ceil(32) {
+        let chained: [u8; 32] = core::array::from_fn(|j| b0[j] ^ prev[j]);
+        let mut bi_input = Bytes::from_array(env, &chained);
+        bi_input.push_back(i as u8);
+        bi_input.append(&dst_prime);
+        prev = env.crypto().sha256(&bi_input).to_array();
+        out.extend_from_array(&prev);
+    }
+    out.slice(..len)
+}
+
+/// RFC 9380 `hash_to_field` to a single scalar, as gnark's `fr.Hash`:
+/// 48 bytes of `expand_message_xmd` read big-endian and reduced modulo r.
+pub fn hash_to_fr(bls: &Bls12_381, msg: &Bytes, dst: &[u8]) -> Fr {
+    let uniform = expand_message_xmd(bls.env(), msg, dst, 48);
+    let mut hi = [0u8; 32];
+    uniform.slice(..16).copy_into_slice(&mut hi[16..]);
+    let mut lo = [0u8; 32];
+    uniform.slice(16..).copy_into_slice(&mut lo);
+
+    let env = bls.env();
+    let hi = Fr::from_bytes(BytesN::from_array(env, &hi));
+    let lo = Fr::from_bytes(BytesN::from_array(env, &lo));
+    let shift = Fr::from_bytes(BytesN::from_array(env, &TWO_POW_256_MOD_R));
+    bls.fr_add(&bls.fr_mul(&hi, &shift), &lo)
+}
+
+/// Whether `bytes` is an uncompressed point encoding the host accepts: the
+/// compression and sort flags unset, the infinity flag only on an otherwise
+/// all-zero encoding, and every coordinate below p. Whether the point is on
+/// the curve is not checked.
+pub fn is_valid_point_encoding(bytes: &[u8]) -> bool {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(48) || bytes[0] & 0xa0 != 0 {
+        return false;
+    }
+    if bytes[0] & 0x40 != 0 {
+        return bytes[0] == 0x40 && bytes[1..].iter().all(|b| *b == 0);
+    }
+    bytes.chunks(48).all(|fp| fp < &FP_MODULUS[..])
+}
+
+/// Replaces a big-endian base field element `y` with `p - y`, leaving 0 as is.
+fn fp_neg(y: &mut [u8]) {
+    if y.iter().all(|b| *b == 0) {
+        return;
+    }
+    let mut borrow = 0u16;
+    for i in (0..48).rev() {
+        let diff = 0x100 + FP_MODULUS[i] as u16 - y[i] as u16 - borrow;
+        y[i] = diff as u8;
+        borrow = 1 - (diff >> 8);
+    }
+}
+
+/// Negates a G1 point by negating its y coordinate in place of the encoding.
+pub fn negate_g1(p: &G1Affine) -> G1Affine {
+    let mut bytes = p.to_array();
+    if bytes[0] & 0x40 == 0 {
+        fp_neg(&mut bytes[48..96]);
+    }
+    G1Affine::from_array(p.env(), &bytes)
+}
+
+/// Negates a G2 point by negating both components of its y coordinate.
+pub fn negate_g2(p: &G2Affine) -> G2Affine {
+    let mut bytes = p.to_array();
+    if bytes[0] & 0x40 == 0 {
+        fp_neg(&mut bytes[96..144]);
+        fp_neg(&mut bytes[144..192]);
+    }
+    G2Affine::from_array(p.env(), &bytes)
+}
+
+/// Decodes a G1 point, checking that the encoding is canonical and that the
+/// point is on the curve and in the prime-order subgroup. The identity is
+/// rejected unless `allow_identity` is set.
+fn g1_from_checked(
+    env: &Env,
+    bytes: &[u8; 96],
+    allow_identity: bool,
+) -> Result<G1Affine, Groth16Error> {
+    if !is_valid_point_encoding(bytes) {
+        return Err(Groth16Error::DecompressingG1Failed);
+    }
+    let p = G1Affine::from_array(env, bytes);
+    if bytes[0] & 0x40 != 0 {
+        return if allow_identity {
+            Ok(p)
+        } else {
+            Err(Groth16Error::PointAtInfinity)
+        };
+    }
+    if !curve::g1_is_on_curve(bytes) {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    if !env.crypto().bls12_381().g1_is_in_subgroup(&p) {
+        return Err(Groth16Error::PointNotInSubgroup);
+    }
+    Ok(p)
+}
+
+/// G2 counterpart of [`g1_from_checked`].
+fn g2_from_checked(
+    env: &Env,
+    bytes: &[u8; 192],
+    allow_identity: bool,
+) -> Result<G2Affine, Groth16Error> {
+    if !is_valid_point_encoding(bytes) {
+        return Err(Groth16Error::DecompressingG2Failed);
+    }
+    let p = G2Affine::from_array(env, bytes);
+    if bytes[0] & 0x40 != 0 {
+        return if allow_identity {
+            Ok(p)
+        } else {
+            Err(Groth16Error::PointAtInfinity)
+        };
+    }
+    if !curve::g2_is_on_curve(bytes) {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    if !env.crypto().bls12_381().g2_is_in_subgroup(&p) {
+        return Err(Groth16Error::PointNotInSubgroup);
+    }
+    Ok(p)
+}
+
+/// Reads the big-endian `u32` at `offset` of a commitment layout.
+fn read_u32(bytes: &Bytes, offset: u32) -> Result<u32, Groth16Error> {
+    if offset.checked_add(4).is_none_or(|end| end > bytes.len()) {
+        return Err(Groth16Error::MalformedCommitments);
+    }
+    let mut buf = [0u8; 4];
+    bytes.slice(offset..offset + 4).copy_into_slice(&mut buf);
+    Ok(u32::from_be_bytes(buf))
+}
+
+impl VerifyingKey {
+    /// Decodes the exact output of arkworks' `serialize_uncompressed` or
+    /// `serialize_compressed` for a `VerifyingKey<Bls12_381>`. That is the
+    /// [`Self::try_from_bytes`] layout with the 8-byte little-endian length
+    /// of `gamma_abc_g1` before its points, which must be
+    /// `public_inputs_len + 1`.
+    pub fn try_from_ark_bytes(bytes: Bytes, public_inputs_len: u32) -> Result<Self, Groth16Error> {
+        // Lengths in u64, which no u32 count can overflow.
+        let n_ic = public_inputs_len as u64 + 1;
+        let prefix_at = match bytes.len() as u64 {
+            len if len == 672 + 8 + 96 * n_ic => 672,
+            len if len == 48 + 96 * 3 + 8 + 48 * n_ic => 48 + 96 * 3,
+            _ => return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs),
+        };
+
+        let mut prefix = [0u8; 8];
+        bytes
+            .slice(prefix_at..prefix_at + 8)
+            .copy_into_slice(&mut prefix);
+        if u64::from_le_bytes(prefix) != n_ic {
+            return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
+        }
+
+        let mut stripped = bytes.slice(..prefix_at);
+        stripped.append(&bytes.slice(prefix_at + 8..));
+        Self::try_from_bytes(stripped, public_inputs_len)
+    }
+}
+
+/// A proof of a circuit using gnark's `Commit` API.
+pub struct CommittedProof {
+    pub proof: Proof,
+    /// gnark Pedersen commitments (BSB22), one per commitment key.
+    pub commitments: Vec<G1Affine>,
+    /// Proof of knowledge of the commitments' openings, folded into one
+    /// point. Set exactly when `commitments` is not empty.
+    pub commitment_pok: Option<G1Affine>,
+}
+
+impl CommittedProof {
+    /// Decodes the uncompressed [`Proof::try_from_bytes`] layout, a
+    /// big-endian `u32` count `m`, the `m` uncompressed commitments and,
+    /// when `m > 0`, the folded proof of knowledge. The commitments and the
+    /// proof of knowledge may be the identity.
+    pub fn try_from_bytes(bytes: Bytes) -> Result<Self, Groth16Error> {
+        if bytes.len() < 384 + 4 {
+            return Err(Groth16Error::MalformedCommitments);
+        }
+        let proof = Proof::try_from_bytes(bytes.slice(..384))?;
+
+        let m = read_u32(&bytes, 384)?;
+        let pok_len = if m == 0 { 0 } else { 96 };
+        if bytes.len() as u64 != 388 + 96 * m as u64 + pok_len {
+            return Err(Groth16Error::MalformedCommitments);
+        }
+        let mut commitments = Vec::new(bytes.env());
+        for i in 0..m {
+            commitments.push_back(groth16::read_g1::<Bls12_381>(
+                &bytes,
+                388 + 96 * i,
+                false,
+                true,
+            )?);
+        }
+        let commitment_pok = if m > 0 {
+            Some(groth16::read_g1::<Bls12_381>(
+                &bytes,
+                388 + 96 * m,
+                false,
+                true,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(CommittedProof {
+            proof,
+            commitments,
+            commitment_pok,
+        })
+    }
+}
+
+/// gnark Pedersen verifying key of one commitment, with the public inputs
+/// the commitment's hash binds.
+#[derive(Clone)]
+pub struct CommitmentKey {
+    pub g: G2Affine,
+    pub g_sigma_neg: G2Affine,
+    /// gnark's `PublicAndCommitmentCommitted` entry. Indices count the
+    /// constant-one wire, so 1 is the first public input; indices past the
+    /// public inputs refer to earlier commitments' hashes.
+    pub public_committed: Vec<u32>,
+}
+
+/// The key of a circuit using gnark's `Commit` API. `vk.gamma_abc_g1` has a
+/// trailing point per commitment, for its hash.
+pub struct CommittedVerifyingKey {
+    pub vk: VerifyingKey,
+    pub commitment_keys: alloc::vec::Vec<CommitmentKey>,
+}
+
+impl CommittedVerifyingKey {
+    /// Decodes a big-endian `u32` count `m`; per commitment, the
+    /// uncompressed `g || g_sigma_neg` and a `u32`-counted list of `u32`
+    /// committed indices; then a key in either [`VerifyingKey::try_from_bytes`]
+    /// layout whose `gamma_abc_g1` has `public_inputs_len + m + 1` points,
+    /// the last `m` for the commitment hashes.
+    pub fn try_from_bytes(bytes: Bytes, public_inputs_len: u32) -> Result<Self, Groth16Error> {
+        let env = bytes.env();
+        let m = read_u32(&bytes, 0)?;
+        let mut offset = 4;
+        let mut commitment_keys = alloc::vec::Vec::new();
+        for _ in 0..m {
+            if bytes.len() - offset < 2 * 192 + 4 {
+                return Err(Groth16Error::MalformedCommitments);
+            }
+            let g = groth16::read_g2::<Bls12_381>(&bytes, offset, false, false)?;
+            let g_sigma_neg = groth16::read_g2::<Bls12_381>(&bytes, offset + 192, false, false)?;
+            let n_committed = read_u32(&bytes, offset + 2 * 192)?;
+            offset += 2 * 192 + 4;
+
+            let mut public_committed = Vec::new(env);
+            for _ in 0..n_committed {
+                public_committed.push_back(read_u32(&bytes, offset)?);
+                offset += 4;
+            }
+            commitment_keys.push(CommitmentKey {
+                g,
+                g_sigma_neg,
+                public_committed,
+            });
+        }
+
+        Ok(CommittedVerifyingKey {
+            vk: VerifyingKey::try_from_bytes(bytes.slice(offset..), public_inputs_len + m)?,
+            commitment_keys,
+        })
+    }
+}
+
+/// Verifies a proof of a circuit using gnark's `Commit` API. `public_inputs`
+/// does not include the commitment hashes, which are derived here.
+pub fn verify_committed_proof(
+    bls: Bls12_381,
+    vk: &CommittedVerifyingKey,
+    proof: &CommittedProof,
+    public_inputs: Vec<Fr>,
+) -> bool {
+    // 1. Check input length
+    let n_commitments = proof.commitments.len();
+    if n_commitments as usize != vk.commitment_keys.len()
+        || (public_inputs.len() + n_commitments + 1) != vk.vk.gamma_abc_g1.len()
+    {
+        return false;
+    }
+
+    // 2. Append the commitment hashes to the inputs and check the
+    // commitments' proof of knowledge
+    let public_inputs = if n_commitments == 0 {
+        public_inputs
+    } else {
+        match commit_inputs(&bls, vk, proof, public_inputs) {
+            Some(public_inputs) => public_inputs,
+            None => return false,
+        }
+    };
+
+    // 3. Compute acc = vk.gamma_abc_g1[0] + sum(input_i * vk.gamma_abc_g1[i]) + sum(commitment_i)
+    let mut acc = groth16::accumulate_inputs(&bls, &vk.vk.gamma_abc_g1, public_inputs);
+    for commitment in proof.commitments.iter() {
+        acc = bls.g1_add(&acc, &commitment);
+    }
+
+    // 4. Pairing check
+    groth16::check_pairing_equation(&bls, &(&vk.vk).into(), &proof.proof, acc)
+}
+
+/// Appends the hash of each gnark commitment, taken over the commitment and
+/// the public inputs it binds, to `public_inputs` as gnark's verifier does.
+/// Then checks the folded proof of knowledge
+/// `prod(e(r^i * commitment_i, g_sigma_neg_i)) * e(pok, g) == 1`, where `r`
+/// hashes the commitment hashes. Returns `None` if a committed index is out
+/// of range or the proof of knowledge fails.
+fn commit_inputs(
+    bls: &Bls12_381,
+    vk: &CommittedVerifyingKey,
+    proof: &CommittedProof,
+    mut public_inputs: Vec<Fr>,
+) -> Option<Vec<Fr>> {
+    let env = bls.env();
+    let mut hashes = Bytes::new(env);
+    for (commitment, key) in proof.commitments.iter().zip(vk.commitment_keys.iter()) {
+        let mut prehash: Bytes = commitment.to_bytes().into();
+        for index in key.public_committed.iter() {
+            let input = public_inputs.get(index.checked_sub(1)?)?;
+            prehash.append(&input.to_bytes().into());
+        }
+        let hash = hash_to_fr(bls, &prehash, COMMITMENT_DST);
+        hashes.append(&hash.to_bytes().into());
+        public_inputs.push_back(hash);
+    }
+
+    // gnark derives every commitment key from one setup, so they share `g`
+    // and a single pairing covers all the openings.
+    let g = &vk.commitment_keys[0].g;
+    let challenge = hash_to_fr(bls, &hashes, COMMITMENT_CHALLENGE_DST);
+    let mut g1: Vec<G1Affine> = Vec::new(env);
+    let mut g2: Vec<G2Affine> = Vec::new(env);
+    let mut r: Option<Fr> = None;
+    for (commitment, key) in proof.commitments.iter().zip(vk.commitment_keys.iter()) {
+        if key.g != *g {
+            return None;
+        }
+        g1.push_back(match &r {
+            None => commitment,
+            Some(r) => bls.g1_mul(&commitment, r),
+        });
+        g2.push_back(key.g_sigma_neg.clone());
+        r = Some(match r {
+            None => challenge.clone(),
+            Some(r) => bls.fr_mul(&r, &challenge),
+        });
+    }
+    g1.push_back(proof.commitment_pok.clone()?);
+    g2.push_back(g.clone());
+
+    bls.pairing_check(g1, g2).then_some(public_inputs)
+}
+
+/// Accumulates proofs, possibly under different keys, into one randomized
+/// pairing check.
+///
+/// Each proof's equation is scaled by its coefficient `r_i` and the
+/// equations are summed. Proofs added under the same key share the alpha,
+/// gamma and delta pairings, so a group of `n` proofs costs `n + 3`
+/// pairings instead of `4n`. The coefficients must be unpredictable to
+/// whoever produced the proofs.
+pub struct BatchVerifier {
+    bls: Bls12_381,
+    g1: Vec<G1Affine>,
+    g2: Vec<G2Affine>,
+}
+
+impl BatchVerifier {
+    pub fn new(bls: Bls12_381) -> Self {
+        let env = bls.env().clone();
+        BatchVerifier {
+            bls,
+            g1: Vec::new(&env),
+            g2: Vec::new(&env),
+        }
+    }
+
+    /// Adds a group of proofs under `pvk`, scaled by the matching `coeffs`.
+    /// Returns false if the group is empty or malformed.
+    pub fn add(
+        &mut self,
+        pvk: &PreparedVerifyingKey,
+        proofs: &[(Proof, Vec<Fr>)],
+        coeffs: Vec<Fr>,
+    ) -> bool {
+        let bls = &self.bls;
+        let env = bls.env();
+        if proofs.is_empty() || proofs.len() as u32 != coeffs.len() {
+            return false;
+        }
+
+        // ic_scalars[j] = sum(r_i * input_ij), with input_i0 = 1, so that
+        // sum(r_i * acc_i) is a single MSM over vk.gamma_abc_g1.
+        let zero = Fr::from_u256(U256::from_u32(env, 0));
+        let mut ic_scalars: Vec<Fr> = Vec::new(env);
+        for _ in 0..pvk.gamma_abc_g1.len() {
+            ic_scalars.push_back(zero.clone());
+        }
+
+        let mut c_points: Vec<G1Affine> = Vec::new(env);
+        for ((proof, public_inputs), r) in proofs.iter().zip(coeffs.iter()) {
+            if (public_inputs.len() + 1) != pvk.gamma_abc_g1.len() {
+                return false;
+            }
+
+            ic_scalars.set(0, bls.fr_add(&ic_scalars.get_unchecked(0), &r));
+            for (j, input) in public_inputs.iter().enumerate() {
+                let j = j as u32 + 1;
+                let term = bls.fr_mul(&r, &input);
+                ic_scalars.set(j, bls.fr_add(&ic_scalars.get_unchecked(j), &term));
+            }
+
+            self.g1.push_back(bls.g1_mul(&proof.a, &r));
+            self.g2.push_back(proof.b.clone());
+            c_points.push_back(proof.c.clone());
+        }
+
+        let r_sum = ic_scalars.get_unchecked(0);
+        self.g1.push_back(bls.g1_mul(&pvk.neg_alpha_g1, &r_sum));
+        self.g2.push_back(pvk.beta_g2.clone());
+        self.g1
+            .push_back(bls.g1_msm(pvk.gamma_abc_g1.clone(), ic_scalars));
+        self.g2.push_back(pvk.neg_gamma_g2.clone());
+        self.g1.push_back(bls.g1_msm(c_points, coeffs));
+        self.g2.push_back(pvk.neg_delta_g2.clone());
+
+        true
+    }
+
+    pub fn verify(self) -> bool {
+        if self.g1.is_empty() {
+            return false;
+        }
+        self.bls.pairing_check(self.g1, self.g2)
+    }
+}
+
+/// Verifies several proofs under the same key with a single pairing check
+/// of `n + 3` pairings. See [`BatchVerifier`].
+pub fn verify_proofs_batch(
+    bls: Bls12_381,
+    pvk: &PreparedVerifyingKey,
+    proofs: &[(Proof, Vec<Fr>)],
+    coeffs: Vec<Fr>,
+) -> bool {
+    let mut batch = BatchVerifier::new(bls);
+    batch.add(pvk, proofs, coeffs) && batch.verify()
+}
@@ -0,0 +1,187 @@
+//! Groth16 over BN254 with the Soroban BN254 host functions, for proofs from
+//! circom, gnark and the zkVM wrappers. Points use the Ethereum encoding: a
+//! 64-byte G1 `x || y` and a 128-byte G2 `x || y` with each coordinate as
+//! `c1 || c0`, all big-endian, and the identity as all zeros.
+
+use soroban_sdk::{
+    crypto::bn254::{Bn254, Bn254G1Affine, Bn254G2Affine, Fr},
+    Bytes, BytesN, Env, Vec,
+};
+
+use crate::{curve_bn254, errors::Groth16Error, groth16, pairing::PairingBackend};
+
+pub type Proof = groth16::Proof<Bn254>;
+pub type VerifyingKey = groth16::VerifyingKey<Bn254>;
+pub type PreparedVerifyingKey = groth16::PreparedVerifyingKey<Bn254>;
+
+/// BN254 base field modulus p, big-endian.
+const FP_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// BN254 scalar field modulus r, big-endian.
+const FR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Parses a big-endian public input, rejecting values not below r.
+pub fn fr_from_canonical_bytes(bytes: BytesN<32>) -> Result<Fr, Groth16Error> {
+    if bytes.to_array() >= FR_MODULUS {
+        return Err(Groth16Error::PublicInputGreaterThenFieldSize);
+    }
+    Ok(Fr::from_bytes(bytes))
+}
+
+/// Whether `bytes` is a point encoding the host accepts: both flag bits
+/// unset and every coordinate below p. Whether the point is on the curve is
+/// not checked.
+pub fn is_valid_point_encoding(bytes: &[u8]) -> bool {
+    !bytes.is_empty()
+        && bytes.len().is_multiple_of(32)
+        && bytes[0] & 0xc0 == 0
+        && bytes.chunks(32).all(|fp| fp < &FP_MODULUS[..])
+}
+
+/// Replaces a big-endian base field element `y` with `p - y`, leaving 0 as is.
+fn fp_neg(y: &mut [u8]) {
+    if y.iter().all(|b| *b == 0) {
+        return;
+    }
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let diff = 0x100 + FP_MODULUS[i] as u16 - y[i] as u16 - borrow;
+        y[i] = diff as u8;
+        borrow = 1 - (diff >> 8);
+    }
+}
+
+/// Negates a G1 point by negating its y coordinate in place of the encoding.
+pub fn negate_g1(p: &Bn254G1Affine) -> Bn254G1Affine {
+    let mut bytes = p.to_array();
+    fp_neg(&mut bytes[32..64]);
+    Bn254G1Affine::from_array(p.env(), &bytes)
+}
+
+/// Negates a G2 point by negating both components of its y coordinate.
+pub fn negate_g2(p: &Bn254G2Affine) -> Bn254G2Affine {
+    let mut bytes = p.to_array();
+    fp_neg(&mut bytes[64..96]);
+    fp_neg(&mut bytes[96..128]);
+    Bn254G2Affine::from_array(p.env(), &bytes)
+}
+
+/// Decodes a G1 point, checking that the encoding is canonical and that the
+/// point is on the curve, which for BN254 G1 implies it is in the subgroup.
+/// The identity is rejected unless `allow_identity` is set.
+fn g1_from_checked(
+    env: &Env,
+    bytes: &[u8; 64],
+    allow_identity: bool,
+) -> Result<Bn254G1Affine, Groth16Error> {
+    if !is_valid_point_encoding(bytes) {
+        return Err(Groth16Error::DecompressingG1Failed);
+    }
+    if bytes.iter().all(|b| *b == 0) {
+        if !allow_identity {
+            return Err(Groth16Error::PointAtInfinity);
+        }
+    } else if !curve_bn254::g1_is_on_curve(bytes) {
+        return Err(Groth16Error::PointNotOnCurve);
+    }
+    Ok(Bn254G1Affine::from_array(env, bytes))
+}
+
+/// G2 counterpart of [`g1_from_checked`]. There is no BN254 subgroup check
+/// host function, and a G2 point outside the subgroup would trap once the
+/// pairing decodes it, so membership is checked here.
+fn g2_from_checked(
+    env: &Env,
+    bytes: &[u8; 128],
+    allow_identity: bool,
+) -> Result<Bn254G2Affine, Groth16Error> {
+    if !is_valid_point_encoding(bytes) {
+        return Err(Groth16Error::DecompressingG2Failed);
+    }
+    if bytes.iter().all(|b| *b == 0) {
+        if !allow_identity {
+            return Err(Groth16Error::PointAtInfinity);
+        }
+    } else if !curve_bn254::g2_is_on_curve(bytes) {
+        return Err(Groth16Error::PointNotOnCurve);
+    } else if !curve_bn254::g2_is_in_subgroup(bytes) {
+        return Err(Groth16Error::PointNotInSubgroup);
+    }
+    Ok(Bn254G2Affine::from_array(env, bytes))
+}
+
+/// There is no BN254 MSM host function, so `g1_msm` keeps the default loop
+/// of a `g1_mul` and a `g1_add` per input. Compressed points are not
+/// supported.
+impl PairingBackend for Bn254 {
+    type G1 = Bn254G1Affine;
+    type G2 = Bn254G2Affine;
+    type Fr = Fr;
+
+    const G1_LEN: u32 = 64;
+    const G2_LEN: u32 = 128;
+
+    fn env(&self) -> &Env {
+        Bn254::env(self)
+    }
+
+    fn g1_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<Bn254G1Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG1Length)?;
+        g1_from_checked(env, &arr, allow_identity)
+    }
+
+    fn g2_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<Bn254G2Affine, Groth16Error> {
+        let arr = bytes
+            .try_into()
+            .map_err(|_| Groth16Error::InvalidG2Length)?;
+        g2_from_checked(env, &arr, allow_identity)
+    }
+
+    fn g1_to_bytes(p: &Bn254G1Affine) -> Bytes {
+        p.to_bytes().into()
+    }
+
+    fn g2_to_bytes(p: &Bn254G2Affine) -> Bytes {
+        p.to_bytes().into()
+    }
+
+    fn fr_from_bytes(bytes: BytesN<32>) -> Result<Fr, Groth16Error> {
+        fr_from_canonical_bytes(bytes)
+    }
+
+    fn g1_neg(p: &Bn254G1Affine) -> Bn254G1Affine {
+        negate_g1(p)
+    }
+
+    fn g2_neg(p: &Bn254G2Affine) -> Bn254G2Affine {
+        negate_g2(p)
+    }
+
+    fn g1_add(&self, p: &Bn254G1Affine, q: &Bn254G1Affine) -> Bn254G1Affine {
+        Bn254::g1_add(self, p, q)
+    }
+
+    fn g1_mul(&self, p: &Bn254G1Affine, scalar: &Fr) -> Bn254G1Affine {
+        Bn254::g1_mul(self, p, scalar)
+    }
+
+    fn pairing_check(&self, g1: Vec<Bn254G1Affine>, g2: Vec<Bn254G2Affine>) -> bool {
+        Bn254::pairing_check(self, g1, g2)
+    }
+}
@@ -0,0 +1,405 @@
+//! Just enough BLS12-381 base field arithmetic to check the curve equations
+//! and decompress points on the guest side. The host traps on points that
+//! are not on the curve, so this runs before any host call sees them.
+
+// Limb loops read better indexed.
+#![allow(clippy::needless_range_loop)]
+
+/// Base field element in Montgomery form, little-endian 64-bit limbs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp([u64; 6]);
+
+const MODULUS: [u64; 6] = [
+    0xb9fe_ffff_ffff_aaab,
+    0x1eab_fffe_b153_ffff,
+    0x6730_d2a0_f6b0_f624,
+    0x6477_4b84_f385_12bf,
+    0x4b1b_a7b6_434b_acd7,
+    0x1a01_11ea_397f_e69a,
+];
+
+/// R^2 mod p with R = 2^384, used to move values into Montgomery form.
+const R2: [u64; 6] = [
+    0xf4df_1f34_1c34_1746,
+    0x0a76_e6a6_09d1_04f1,
+    0x8de5_476c_4c95_b6d5,
+    0x67eb_88a9_939d_83c0,
+    0x9a79_3e85_b519_952d,
+    0x1198_8fe5_92ca_e3aa,
+];
+
+/// -p^-1 mod 2^64.
+const INV: u64 = 0x89f3_fffc_fffc_fffd;
+
+/// (p + 1) / 4, the square root exponent since p = 3 mod 4.
+const P_PLUS_1_DIV_4: [u64; 6] = [
+    0xee7f_bfff_ffff_eaab,
+    0x07aa_ffff_ac54_ffff,
+    0xd9cc_34a8_3dac_3d89,
+    0xd91d_d2e1_3ce1_44af,
+    0x92c6_e9ed_90d2_eb35,
+    0x0680_447a_8e5f_f9a6,
+];
+
+/// (p - 3) / 4.
+const P_MINUS_3_DIV_4: [u64; 6] = [
+    0xee7f_bfff_ffff_eaaa,
+    0x07aa_ffff_ac54_ffff,
+    0xd9cc_34a8_3dac_3d89,
+    0xd91d_d2e1_3ce1_44af,
+    0x92c6_e9ed_90d2_eb35,
+    0x0680_447a_8e5f_f9a6,
+];
+
+/// (p - 1) / 2.
+const P_MINUS_1_DIV_2: [u64; 6] = [
+    0xdcff_7fff_ffff_d555,
+    0x0f55_ffff_58a9_ffff,
+    0xb398_6950_7b58_7b12,
+    0xb23b_a5c2_79c2_895f,
+    0x258d_d3db_21a5_d66b,
+    0x0d00_88f5_1cbf_f34d,
+];
+
+/// Big-endian limb comparison `a > b`.
+fn limbs_gt(a: &[u64; 6], b: &[u64; 6]) -> bool {
+    for i in (0..6).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    false
+}
+
+impl Fp {
+    const ZERO: Fp = Fp([0; 6]);
+
+    /// Parses a big-endian element the caller has already checked is below p.
+    fn from_be_bytes(bytes: &[u8]) -> Fp {
+        Fp::from_be_bytes_checked(bytes).unwrap()
+    }
+
+    /// Parses a big-endian element, or `None` if it is not below p.
+    fn from_be_bytes_checked(bytes: &[u8]) -> Option<Fp> {
+        let mut limbs = [0u64; 6];
+        for (i, chunk) in bytes.rchunks(8).enumerate() {
+            limbs[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        if !limbs_gt(&MODULUS, &limbs) {
+            return None;
+        }
+        Some(Fp(limbs).mul(&Fp(R2)))
+    }
+
+    /// The canonical representative, out of Montgomery form.
+    fn to_canonical(self) -> [u64; 6] {
+        self.mul(&Fp([1, 0, 0, 0, 0, 0])).0
+    }
+
+    fn to_be_bytes(self) -> [u8; 48] {
+        let mut out = [0u8; 48];
+        for (i, limb) in self.to_canonical().iter().enumerate() {
+            out[40 - 8 * i..48 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+        }
+        out
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Fp::ZERO
+    }
+
+    /// Whether the element is greater than its negation, which is what the
+    /// ZCash sort flag records.
+    fn is_lexicographically_largest(&self) -> bool {
+        limbs_gt(&self.to_canonical(), &P_MINUS_1_DIV_2)
+    }
+
+    fn from_u64(v: u64) -> Fp {
+        Fp([v, 0, 0, 0, 0, 0]).mul(&Fp(R2))
+    }
+
+    /// Subtracts p once if `self` is not below it.
+    fn reduce(self) -> Fp {
+        let mut out = [0u64; 6];
+        let mut borrow = 0u64;
+        for i in 0..6 {
+            let (d, b0) = self.0[i].overflowing_sub(MODULUS[i]);
+            let (d, b1) = d.overflowing_sub(borrow);
+            out[i] = d;
+            borrow = (b0 | b1) as u64;
+        }
+        if borrow == 0 {
+            Fp(out)
+        } else {
+            self
+        }
+    }
+
+    fn add(&self, rhs: &Fp) -> Fp {
+        let mut out = [0u64; 6];
+        let mut carry = 0u64;
+        for i in 0..6 {
+            let (s, c0) = self.0[i].overflowing_add(rhs.0[i]);
+            let (s, c1) = s.overflowing_add(carry);
+            out[i] = s;
+            carry = (c0 | c1) as u64;
+        }
+        // p < 2^381, so the sum of two reduced elements never carries out.
+        Fp(out).reduce()
+    }
+
+    fn sub(&self, rhs: &Fp) -> Fp {
+        let mut out = [0u64; 6];
+        let mut borrow = 0u64;
+        for i in 0..6 {
+            let (d, b0) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (d, b1) = d.overflowing_sub(borrow);
+            out[i] = d;
+            borrow = (b0 | b1) as u64;
+        }
+        if borrow != 0 {
+            let mut carry = 0u64;
+            for i in 0..6 {
+                let (s, c0) = out[i].overflowing_add(MODULUS[i]);
+                let (s, c1) = s.overflowing_add(carry);
+                out[i] = s;
+                carry = (c0 | c1) as u64;
+            }
+        }
+        Fp(out)
+    }
+
+    fn neg(&self) -> Fp {
+        Fp::ZERO.sub(self)
+    }
+
+    fn square(&self) -> Fp {
+        self.mul(self)
+    }
+
+    /// Square-and-multiply over a public exponent.
+    fn pow(&self, exp: &[u64; 6]) -> Fp {
+        let mut res = Fp::from_u64(1);
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                res = res.square();
+                if (limb >> bit) & 1 == 1 {
+                    res = res.mul(self);
+                }
+            }
+        }
+        res
+    }
+
+    fn sqrt(&self) -> Option<Fp> {
+        let s = self.pow(&P_PLUS_1_DIV_4);
+        (s.square() == *self).then_some(s)
+    }
+
+    /// Montgomery multiplication (CIOS).
+    fn mul(&self, rhs: &Fp) -> Fp {
+        let mut t = [0u64; 8];
+        for i in 0..6 {
+            let mut carry = 0u128;
+            for j in 0..6 {
+                let v = t[j] as u128 + self.0[j] as u128 * rhs.0[i] as u128 + carry;
+                t[j] = v as u64;
+                carry = v >> 64;
+            }
+            let v = t[6] as u128 + carry;
+            t[6] = v as u64;
+            t[7] = (v >> 64) as u64;
+
+            let m = t[0].wrapping_mul(INV);
+            let mut carry = (t[0] as u128 + m as u128 * MODULUS[0] as u128) >> 64;
+            for j in 1..6 {
+                let v = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry;
+                t[j - 1] = v as u64;
+                carry = v >> 64;
+            }
+            let v = t[6] as u128 + carry;
+            t[5] = v as u64;
+            t[6] = t[7] + (v >> 64) as u64;
+        }
+        Fp([t[0], t[1], t[2], t[3], t[4], t[5]]).reduce()
+    }
+}
+
+/// `c0 + c1 * u` with `u^2 = -1`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp2 {
+    c0: Fp,
+    c1: Fp,
+}
+
+impl Fp2 {
+    /// Parses the `c1 || c0` big-endian encoding Soroban uses.
+    fn from_be_bytes(bytes: &[u8]) -> Fp2 {
+        Fp2 {
+            c0: Fp::from_be_bytes(&bytes[48..96]),
+            c1: Fp::from_be_bytes(&bytes[0..48]),
+        }
+    }
+
+    fn from_be_bytes_checked(bytes: &[u8]) -> Option<Fp2> {
+        Some(Fp2 {
+            c0: Fp::from_be_bytes_checked(&bytes[48..96])?,
+            c1: Fp::from_be_bytes_checked(&bytes[0..48])?,
+        })
+    }
+
+    fn to_be_bytes(self) -> [u8; 96] {
+        let mut out = [0u8; 96];
+        out[0..48].copy_from_slice(&self.c1.to_be_bytes());
+        out[48..96].copy_from_slice(&self.c0.to_be_bytes());
+        out
+    }
+
+    fn one() -> Fp2 {
+        Fp2 {
+            c0: Fp::from_u64(1),
+            c1: Fp::ZERO,
+        }
+    }
+
+    fn is_lexicographically_largest(&self) -> bool {
+        self.c1.is_lexicographically_largest()
+            || (self.c1.is_zero() && self.c0.is_lexicographically_largest())
+    }
+
+    fn neg(&self) -> Fp2 {
+        Fp2 {
+            c0: self.c0.neg(),
+            c1: self.c1.neg(),
+        }
+    }
+
+    fn square(&self) -> Fp2 {
+        self.mul(self)
+    }
+
+    fn pow(&self, exp: &[u64; 6]) -> Fp2 {
+        let mut res = Fp2::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                res = res.square();
+                if (limb >> bit) & 1 == 1 {
+                    res = res.mul(self);
+                }
+            }
+        }
+        res
+    }
+
+    /// Algorithm 9 of <https://eprint.iacr.org/2012/685.pdf>, for p = 3 mod 4.
+    fn sqrt(&self) -> Option<Fp2> {
+        let a1 = self.pow(&P_MINUS_3_DIV_4);
+        let alpha = a1.square().mul(self);
+        let x0 = a1.mul(self);
+        let s = if alpha == Fp2::one().neg() {
+            // x0 * u
+            Fp2 {
+                c0: x0.c1.neg(),
+                c1: x0.c0,
+            }
+        } else {
+            alpha.add(&Fp2::one()).pow(&P_MINUS_1_DIV_2).mul(&x0)
+        };
+        (s.square() == *self).then_some(s)
+    }
+
+    fn add(&self, rhs: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0.add(&rhs.c0),
+            c1: self.c1.add(&rhs.c1),
+        }
+    }
+
+    fn mul(&self, rhs: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0.mul(&rhs.c0).sub(&self.c1.mul(&rhs.c1)),
+            c1: self.c0.mul(&rhs.c1).add(&self.c1.mul(&rhs.c0)),
+        }
+    }
+}
+
+/// Whether the uncompressed, non-infinity G1 encoding satisfies
+/// `y^2 = x^3 + 4`. The encoding must already be known to be canonical.
+pub fn g1_is_on_curve(bytes: &[u8; 96]) -> bool {
+    let x = Fp::from_be_bytes(&bytes[0..48]);
+    let y = Fp::from_be_bytes(&bytes[48..96]);
+    y.mul(&y) == x.mul(&x).mul(&x).add(&Fp::from_u64(4))
+}
+
+/// Whether the uncompressed, non-infinity G2 encoding satisfies
+/// `y^2 = x^3 + 4(1 + u)`. The encoding must already be known to be canonical.
+pub fn g2_is_on_curve(bytes: &[u8; 192]) -> bool {
+    let x = Fp2::from_be_bytes(&bytes[0..96]);
+    let y = Fp2::from_be_bytes(&bytes[96..192]);
+    let four = Fp::from_u64(4);
+    let b = Fp2 { c0: four, c1: four };
+    y.mul(&y) == x.mul(&x).mul(&x).add(&b)
+}
+
+/// ZCash flag bits in the first byte of a point encoding.
+const FLAG_COMPRESSED: u8 = 0x80;
+const FLAG_INFINITY: u8 = 0x40;
+const FLAG_SORT: u8 = 0x20;
+
+/// Checks the flags of a compressed encoding. Returns `Ok(None)` for the
+/// point at infinity, or the x coordinate bytes with the flags cleared and
+/// whether the sort flag asks for the larger y.
+fn split_compressed<const N: usize>(bytes: &[u8; N]) -> Result<Option<([u8; N], bool)>, ()> {
+    let flags = bytes[0] & 0xe0;
+    if flags & FLAG_COMPRESSED == 0 {
+        return Err(());
+    }
+    let mut x = *bytes;
+    x[0] &= 0x1f;
+    if flags & FLAG_INFINITY != 0 {
+        return if flags & FLAG_SORT == 0 && x.iter().all(|b| *b == 0) {
+            Ok(None)
+        } else {
+            Err(())
+        };
+    }
+    Ok(Some((x, flags & FLAG_SORT != 0)))
+}
+
+/// Decompresses a ZCash-encoded G1 point into the uncompressed encoding the
+/// host takes. Fails if the flags are inconsistent, x is not below p, or x
+/// is not the abscissa of a curve point. Subgroup membership is not checked.
+pub fn g1_decompress(bytes: &[u8; 48]) -> Option<[u8; 96]> {
+    let mut out = [0u8; 96];
+    let Some((x, largest)) = split_compressed(bytes).ok()? else {
+        out[0] = FLAG_INFINITY;
+        return Some(out);
+    };
+    let x = Fp::from_be_bytes_checked(&x)?;
+    let mut y = x.square().mul(&x).add(&Fp::from_u64(4)).sqrt()?;
+    if y.is_lexicographically_largest() != largest {
+        y = y.neg();
+    }
+    out[0..48].copy_from_slice(&x.to_be_bytes());
+    out[48..96].copy_from_slice(&y.to_be_bytes());
+    Some(out)
+}
+
+/// G2 counterpart of [`g1_decompress`]; x is encoded as `c1 || c0`.
+pub fn g2_decompress(bytes: &[u8; 96]) -> Option<[u8; 192]> {
+    let mut out = [0u8; 192];
+    let Some((x, largest)) = split_compressed(bytes).ok()? else {
+        out[0] = FLAG_INFINITY;
+        return Some(out);
+    };
+    let x = Fp2::from_be_bytes_checked(&x)?;
+    let four = Fp::from_u64(4);
+    let b = Fp2 { c0: four, c1: four };
+    let mut y = x.square().mul(&x).add(&b).sqrt()?;
+    if y.is_lexicographically_largest() != largest {
+        y = y.neg();
+    }
+    out[0..96].copy_from_slice(&x.to_be_bytes());
+    out[96..192].copy_from_slice(&y.to_be_bytes());
+    Some(out)
+}
@@ -0,0 +1,355 @@
+//! Just enough BN254 base field arithmetic to check the curve equations and
+//! G2 subgroup membership on the guest side, so malformed points are
+//! rejected with an error instead of trapping in the host.
+
+// Limb loops read better indexed.
+#![allow(clippy::needless_range_loop)]
+
+/// Base field element in Montgomery form, little-endian 64-bit limbs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp([u64; 4]);
+
+const MODULUS: [u64; 4] = [
+    0x3c20_8c16_d87c_fd47,
+    0x9781_6a91_6871_ca8d,
+    0xb850_45b6_8181_585d,
+    0x3064_4e72_e131_a029,
+];
+
+/// R^2 mod p with R = 2^256, used to move values into Montgomery form.
+const R2: [u64; 4] = [
+    0xf32c_fc5b_538a_fa89,
+    0xb5e7_1911_d445_01fb,
+    0x47ab_1eff_0a41_7ff6,
+    0x06d8_9f71_cab8_351f,
+];
+
+/// -p^-1 mod 2^64.
+const INV: u64 = 0x87d2_0782_e486_6389;
+
+/// The G2 curve constant `3 / (9 + u)`, real and imaginary parts.
+const TWIST_B_C0: [u64; 4] = [
+    0x3267_e6dc_24a1_38e5,
+    0xb5b4_c5e5_59db_efa3,
+    0x81be_1899_1be0_6ac3,
+    0x2b14_9d40_ceb8_aaae,
+];
+const TWIST_B_C1: [u64; 4] = [
+    0xe4a2_bd06_85c3_15d2,
+    0xa74f_a084_e52d_1852,
+    0xcd2c_afad_eed8_fdf4,
+    0x0097_13b0_3af0_fed4,
+];
+
+/// The coefficients of the untwist-Frobenius-twist endomorphism
+/// `psi(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y)`, which are
+/// `(9 + u)^((p - 1) / 3)` and `(9 + u)^((p - 1) / 2)`.
+const PSI_X_C0: [u64; 4] = [
+    0x99e3_9557_176f_553d,
+    0xb78c_c310_c2c3_330c,
+    0x4c0b_ec3c_f559_b143,
+    0x2fb3_4798_4f79_11f7,
+];
+const PSI_X_C1: [u64; 4] = [
+    0x1665_d51c_640f_cba2,
+    0x32ae_2a1d_0b7c_9dce,
+    0x4ba4_cc8b_d75a_0794,
+    0x16c9_e550_61eb_ae20,
+];
+const PSI_Y_C0: [u64; 4] = [
+    0xdc54_0146_71a0_135a,
+    0xdbaa_e0ed_a9c9_5998,
+    0xdc5e_c698_b6e2_f9b9,
+    0x063c_f305_489a_f5dc,
+];
+const PSI_Y_C1: [u64; 4] = [
+    0x82d3_7f63_2623_b0e3,
+    0x2180_7dc9_8fa2_5bd2,
+    0x0704_b5a7_ec79_6f2b,
+    0x07c0_3cbc_ac41_049a,
+];
+
+/// `6x^2` for the BN254 curve parameter `x`.
+const SIX_X_SQUARED: u128 = 0x6f4d_8248_eeb8_59fb_f83e_9682_e87c_fd46;
+
+impl Fp {
+    const ZERO: Fp = Fp([0; 4]);
+
+    /// Parses a big-endian element the caller has already checked is below p.
+    fn from_be_bytes(bytes: &[u8]) -> Fp {
+        let mut limbs = [0u64; 4];
+        for (i, chunk) in bytes.rchunks(8).enumerate() {
+            limbs[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        Fp(limbs).mul(&Fp(R2))
+    }
+
+    fn from_canonical(limbs: [u64; 4]) -> Fp {
+        Fp(limbs).mul(&Fp(R2))
+    }
+
+    /// Subtracts p once if `self` is not below it.
+    fn reduce(self) -> Fp {
+        let mut out = [0u64; 4];
+        let mut borrow = 0u64;
+        for i in 0..4 {
+            let (d, b0) = self.0[i].overflowing_sub(MODULUS[i]);
+            let (d, b1) = d.overflowing_sub(borrow);
+            out[i] = d;
+            borrow = (b0 | b1) as u64;
+        }
+        if borrow == 0 {
+            Fp(out)
+        } else {
+            self
+        }
+    }
+
+    fn add(&self, rhs: &Fp) -> Fp {
+        let mut out = [0u64; 4];
+        let mut carry = 0u64;
+        for i in 0..4 {
+            let (s, c0) = self.0[i].overflowing_add(rhs.0[i]);
+            let (s, c1) = s.overflowing_add(carry);
+            out[i] = s;
+            carry = (c0 | c1) as u64;
+        }
+        // p < 2^254, so the sum of two reduced elements never carries out.
+        Fp(out).reduce()
+    }
+
+    fn sub(&self, rhs: &Fp) -> Fp {
+        let mut out = [0u64; 4];
+        let mut borrow = 0u64;
+        for i in 0..4 {
+            let (d, b0) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (d, b1) = d.overflowing_sub(borrow);
+            out[i] = d;
+            borrow = (b0 | b1) as u64;
+        }
+        if borrow != 0 {
+            let mut carry = 0u64;
+            for i in 0..4 {
+                let (s, c0) = out[i].overflowing_add(MODULUS[i]);
+                let (s, c1) = s.overflowing_add(carry);
+                out[i] = s;
+                carry = (c0 | c1) as u64;
+            }
+        }
+        Fp(out)
+    }
+
+    /// Montgomery multiplication (CIOS).
+    fn mul(&self, rhs: &Fp) -> Fp {
+        let mut t = [0u64; 6];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let v = t[j] as u128 + self.0[j] as u128 * rhs.0[i] as u128 + carry;
+                t[j] = v as u64;
+                carry = v >> 64;
+            }
+            let v = t[4] as u128 + carry;
+            t[4] = v as u64;
+            t[5] = (v >> 64) as u64;
+
+            let m = t[0].wrapping_mul(INV);
+            let mut carry = (t[0] as u128 + m as u128 * MODULUS[0] as u128) >> 64;
+            for j in 1..4 {
+                let v = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry;
+                t[j - 1] = v as u64;
+                carry = v >> 64;
+            }
+            let v = t[4] as u128 + carry;
+            t[3] = v as u64;
+            t[4] = t[5] + (v >> 64) as u64;
+        }
+        Fp([t[0], t[1], t[2], t[3]]).reduce()
+    }
+}
+
+/// `c0 + c1 * u` with `u^2 = -1`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fp2 {
+    c0: Fp,
+    c1: Fp,
+}
+
+impl Fp2 {
+    /// Parses the `c1 || c0` big-endian encoding Soroban uses.
+    fn from_be_bytes(bytes: &[u8]) -> Fp2 {
+        Fp2 {
+            c0: Fp::from_be_bytes(&bytes[32..64]),
+            c1: Fp::from_be_bytes(&bytes[0..32]),
+        }
+    }
+
+    fn add(&self, rhs: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0.add(&rhs.c0),
+            c1: self.c1.add(&rhs.c1),
+        }
+    }
+
+    fn sub(&self, rhs: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0.sub(&rhs.c0),
+            c1: self.c1.sub(&rhs.c1),
+        }
+    }
+
+    fn double(&self) -> Fp2 {
+        self.add(self)
+    }
+
+    fn mul(&self, rhs: &Fp2) -> Fp2 {
+        Fp2 {
+            c0: self.c0.mul(&rhs.c0).sub(&self.c1.mul(&rhs.c1)),
+            c1: self.c0.mul(&rhs.c1).add(&self.c1.mul(&rhs.c0)),
+        }
+    }
+
+    fn square(&self) -> Fp2 {
+        self.mul(self)
+    }
+
+    fn conjugate(&self) -> Fp2 {
+        Fp2 {
+            c0: self.c0,
+            c1: Fp::ZERO.sub(&self.c1),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0 == Fp::ZERO && self.c1 == Fp::ZERO
+    }
+
+    fn from_canonical(c0: [u64; 4], c1: [u64; 4]) -> Fp2 {
+        Fp2 {
+            c0: Fp::from_canonical(c0),
+            c1: Fp::from_canonical(c1),
+        }
+    }
+}
+
+/// A G2 point in Jacobian coordinates, `(X / Z^2, Y / Z^3)`, with `Z = 0`
+/// for the identity.
+#[derive(Clone, Copy)]
+struct G2Jacobian {
+    x: Fp2,
+    y: Fp2,
+    z: Fp2,
+}
+
+impl G2Jacobian {
+    fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// `dbl-2009-l`, for curves with `a = 0`.
+    fn double(&self) -> G2Jacobian {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let x = e.square().sub(&d.double());
+        let c8 = c.double().double().double();
+        G2Jacobian {
+            x,
+            y: e.mul(&d.sub(&x)).sub(&c8),
+            z: self.y.mul(&self.z).double(),
+        }
+    }
+
+    /// Adds the affine point `(x2, y2)` with `madd-2007-bl`, falling back to
+    /// doubling or the identity when the points share an x coordinate.
+    fn add_affine(&self, x2: &Fp2, y2: &Fp2) -> G2Jacobian {
+        let one = Fp2::from_canonical([1, 0, 0, 0], [0; 4]);
+        if self.is_identity() {
+            return G2Jacobian {
+                x: *x2,
+                y: *y2,
+                z: one,
+            };
+        }
+        let z1z1 = self.z.square();
+        let u2 = x2.mul(&z1z1);
+        let s2 = y2.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y).double();
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                G2Jacobian {
+                    x: one,
+                    y: one,
+                    z: Fp2 {
+                        c0: Fp::ZERO,
+                        c1: Fp::ZERO,
+                    },
+                }
+            };
+        }
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h.mul(&i);
+        let v = self.x.mul(&i);
+        let x = r.square().sub(&j).sub(&v.double());
+        G2Jacobian {
+            x,
+            y: r.mul(&v.sub(&x)).sub(&self.y.mul(&j).double()),
+            z: self.z.add(&h).square().sub(&z1z1).sub(&hh),
+        }
+    }
+}
+
+/// Whether the non-infinity G1 encoding satisfies `y^2 = x^3 + 3`. The
+/// encoding must already be known to be canonical.
+pub fn g1_is_on_curve(bytes: &[u8; 64]) -> bool {
+    let x = Fp::from_be_bytes(&bytes[0..32]);
+    let y = Fp::from_be_bytes(&bytes[32..64]);
+    y.mul(&y) == x.mul(&x).mul(&x).add(&Fp::from_canonical([3, 0, 0, 0]))
+}
+
+/// Whether the non-infinity G2 encoding satisfies `y^2 = x^3 + 3 / (9 + u)`.
+/// The encoding must already be known to be canonical.
+pub fn g2_is_on_curve(bytes: &[u8; 128]) -> bool {
+    let x = Fp2::from_be_bytes(&bytes[0..64]);
+    let y = Fp2::from_be_bytes(&bytes[64..128]);
+    let b = Fp2 {
+        c0: Fp::from_canonical(TWIST_B_C0),
+        c1: Fp::from_canonical(TWIST_B_C1),
+    };
+    y.mul(&y) == x.mul(&x).mul(&x).add(&b)
+}
+
+/// Whether the non-infinity G2 encoding, already known to be canonical and
+/// on the curve, is in the prime-order subgroup: `psi(P) == [6x^2] P`, the
+/// test of El Housni, Guillevic and Piellard.
+pub fn g2_is_in_subgroup(bytes: &[u8; 128]) -> bool {
+    let x = Fp2::from_be_bytes(&bytes[0..64]);
+    let y = Fp2::from_be_bytes(&bytes[64..128]);
+
+    let mut acc = G2Jacobian {
+        x,
+        y,
+        z: Fp2::from_canonical([1, 0, 0, 0], [0; 4]),
+    };
+    for i in (0..SIX_X_SQUARED.ilog2()).rev() {
+        acc = acc.double();
+        if (SIX_X_SQUARED >> i) & 1 == 1 {
+            acc = acc.add_affine(&x, &y);
+        }
+    }
+    if acc.is_identity() {
+        return false;
+    }
+
+    // Compare with psi(P) without leaving Jacobian coordinates.
+    let psi_x = x.conjugate().mul(&Fp2::from_canonical(PSI_X_C0, PSI_X_C1));
+    let psi_y = y.conjugate().mul(&Fp2::from_canonical(PSI_Y_C0, PSI_Y_C1));
+    let z2 = acc.z.square();
+    acc.x == psi_x.mul(&z2) && acc.y == psi_y.mul(&z2).mul(&acc.z)
+}
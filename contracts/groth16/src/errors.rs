@@ -14,4 +14,22 @@ pub enum Groth16Error {
     DecompressingG1Failed = 8,
     DecompressingG2Failed = 9,
     PublicInputGreaterThenFieldSize = 10,
+    VerifyingKeyNotFound = 11,
+    CircuitNotFound = 12,
+    NoPendingAdmin = 13,
+    VerifyingKeyRevoked = 14,
+    CircuitAlreadyBound = 15,
+    PendingChangeNotFound = 16,
+    PendingChangeExists = 17,
+    TimelockNotElapsed = 18,
+    InvalidTtlConfig = 19,
+    EmptyBatch = 20,
+    PointAtInfinity = 21,
+    PointNotOnCurve = 22,
+    PointNotInSubgroup = 23,
+    MalformedCommitments = 24,
+    InvalidSp1ProofLength = 25,
+    Sp1VkSelectorMismatch = 26,
+    InvalidUpgradeDelay = 27,
+    VerifyingKeyInUse = 28,
 }
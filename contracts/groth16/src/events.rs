@@ -0,0 +1,51 @@
+use soroban_sdk::{contractevent, BytesN, Symbol};
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkDeprecated {
+    #[topic]
+    pub vk_id: BytesN<32>,
+    pub sunset_ledger: Option<u32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkRevoked {
+    #[topic]
+    pub vk_id: BytesN<32>,
+}
+
+/// Emitted whenever a proof is verified against a deprecated key.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeprecatedVkUsed {
+    #[topic]
+    pub vk_id: BytesN<32>,
+    pub version: u32,
+    pub sunset_ledger: Option<u32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitVkProposed {
+    #[topic]
+    pub circuit: Symbol,
+    pub vk_id: BytesN<32>,
+    pub eta_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitVkExecuted {
+    #[topic]
+    pub circuit: Symbol,
+    pub vk_id: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitVkCancelled {
+    #[topic]
+    pub circuit: Symbol,
+    pub vk_id: BytesN<32>,
+}
@@ -1,110 +1,139 @@
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use soroban_sdk::{
-    crypto::bls12_381::{Bls12_381, Fr, G1Affine, G2Affine},
-    vec, Bytes, BytesN, Vec,
-};
+//! Curve-generic Groth16 parsing and verification over a
+//! [`PairingBackend`]. The curve-specific encodings and extensions live in
+//! [`crate::bls12_381`] and [`crate::bn254`].
 
-use ark_bls12_381::G1Affine as G1AffineArk;
+use soroban_sdk::{vec, Bytes, BytesN, Env, Vec};
 
-use crate::errors::Groth16Error;
+use crate::{errors::Groth16Error, pairing::PairingBackend};
 
-pub struct Proof {
-    pub a: G1Affine,
-    pub b: G2Affine,
-    pub c: G1Affine,
+pub struct Proof<E: PairingBackend> {
+    pub a: E::G1,
+    pub b: E::G2,
+    pub c: E::G1,
 }
 
-impl Proof {
-    pub fn try_from_bytes(bytes: BytesN<384>) -> Result<Self, Groth16Error> {
-        let arr = &bytes.to_array();
-
-        let a = G1Affine::from_array(
-            &bytes.env(),
-            &arr[0..96]
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG1Length)?,
-        );
-
-        let b = G2Affine::from_array(
-            &bytes.env(),
-            &arr[96..288]
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG2Length)?,
-        );
-        let c = G1Affine::from_array(
-            &bytes.env(),
-            &arr[288..384]
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG1Length)?,
-        );
-
-        Ok(Proof { a, b, c })
+impl<E: PairingBackend> Proof<E> {
+    /// Decodes the uncompressed `a || b || c`, rejecting any point that is
+    /// malformed, off the curve, outside the subgroup or the identity.
+    pub fn try_from_bytes(bytes: Bytes) -> Result<Self, Groth16Error> {
+        Self::decode(bytes, E::G1_LEN, E::G2_LEN, false)
+    }
+
+    /// Decodes `a || b || c` with each point compressed, validating them
+    /// like [`Self::try_from_bytes`].
+    pub fn try_from_compressed_bytes(bytes: Bytes) -> Result<Self, Groth16Error> {
+        let (g1_len, g2_len) = E::COMPRESSED_LENS.ok_or(Groth16Error::DecompressingG1Failed)?;
+        Self::decode(bytes, g1_len, g2_len, true)
+    }
+
+    fn decode(
+        bytes: Bytes,
+        g1_len: u32,
+        g2_len: u32,
+        compressed: bool,
+    ) -> Result<Self, Groth16Error> {
+        if bytes.len() != 2 * g1_len + g2_len {
+            return Err(Groth16Error::InvalidG1Length);
+        }
+        Ok(Proof {
+            a: read_g1::<E>(&bytes, 0, compressed, false)?,
+            b: read_g2::<E>(&bytes, g1_len, compressed, false)?,
+            c: read_g1::<E>(&bytes, g1_len + g2_len, compressed, false)?,
+        })
     }
 }
 
-pub struct VerifyingKey {
-    pub alpha_g1: G1Affine,
-    pub beta_g2: G2Affine,
-    pub gamma_g2: G2Affine,
-    pub delta_g2: G2Affine,
-    pub gamma_abc_g1: Vec<G1Affine>, // precomputed public inputs
+/// Reads the G1 point at `offset`, in either encoding.
+pub(crate) fn read_g1<E: PairingBackend>(
+    bytes: &Bytes,
+    offset: u32,
+    compressed: bool,
+    allow_identity: bool,
+) -> Result<E::G1, Groth16Error> {
+    let len = match (compressed, E::COMPRESSED_LENS) {
+        (false, _) => E::G1_LEN,
+        (true, Some((len, _))) => len,
+        (true, None) => return Err(Groth16Error::DecompressingG1Failed),
+    };
+    if offset.checked_add(len).is_none_or(|end| end > bytes.len()) {
+        return Err(Groth16Error::InvalidG1Length);
+    }
+    let point = bytes.slice(offset..offset + len);
+    if compressed {
+        E::g1_from_compressed(bytes.env(), &point, allow_identity)
+    } else {
+        E::g1_from_bytes(bytes.env(), &point, allow_identity)
+    }
 }
 
-impl VerifyingKey {
+/// G2 counterpart of [`read_g1`].
+pub(crate) fn read_g2<E: PairingBackend>(
+    bytes: &Bytes,
+    offset: u32,
+    compressed: bool,
+    allow_identity: bool,
+) -> Result<E::G2, Groth16Error> {
+    let len = match (compressed, E::COMPRESSED_LENS) {
+        (false, _) => E::G2_LEN,
+        (true, Some((_, len))) => len,
+        (true, None) => return Err(Groth16Error::DecompressingG2Failed),
+    };
+    if offset.checked_add(len).is_none_or(|end| end > bytes.len()) {
+        return Err(Groth16Error::InvalidG2Length);
+    }
+    let point = bytes.slice(offset..offset + len);
+    if compressed {
+        E::g2_from_compressed(bytes.env(), &point, allow_identity)
+    } else {
+        E::g2_from_bytes(bytes.env(), &point, allow_identity)
+    }
+}
+
+pub struct VerifyingKey<E: PairingBackend> {
+    pub alpha_g1: E::G1,
+    pub beta_g2: E::G2,
+    pub gamma_g2: E::G2,
+    pub delta_g2: E::G2,
+    pub gamma_abc_g1: Vec<E::G1>, // precomputed public inputs
+}
+
+impl<E: PairingBackend> VerifyingKey<E> {
+    /// Decodes a key with `public_inputs_len` inputs from either layout,
+    /// told apart by length: uncompressed points or, if the backend has
+    /// them, compressed points, both in the order
+    /// alpha || beta || gamma || delta || gamma_abc_g1. Every point is
+    /// validated like [`Proof::try_from_bytes`]; only `gamma_abc_g1` may
+    /// hold the identity.
     pub fn try_from_bytes(bytes: Bytes, public_inputs_len: u32) -> Result<Self, Groth16Error> {
-        // Check vk length: must be equal to 672 + 96 * n
-        // where n is the number of public inputs
-        if bytes.len() != 672 + 96 * (public_inputs_len + 1) {
-            return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
-        }
+        // `public_inputs_len` is caller input: a count whose layout does not
+        // fit in a u32 cannot match any key.
+        let n_ic = public_inputs_len
+            .checked_add(1)
+            .ok_or(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs)?;
+        let layout_len = |(g1_len, g2_len): (u32, u32)| {
+            g1_len
+                .checked_mul(n_ic.checked_add(1)?)?
+                .checked_add(g2_len * 3)
+        };
+        let compressed = match Some(bytes.len()) {
+            len if len == layout_len((E::G1_LEN, E::G2_LEN)) => false,
+            len if E::COMPRESSED_LENS.and_then(layout_len) == len => true,
+            _ => return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs),
+        };
+        let (g1_len, g2_len) = match E::COMPRESSED_LENS {
+            Some(lens) if compressed => lens,
+            _ => (E::G1_LEN, E::G2_LEN),
+        };
+
+        let alpha = read_g1::<E>(&bytes, 0, compressed, false)?;
+        let beta = read_g2::<E>(&bytes, g1_len, compressed, false)?;
+        let gamma = read_g2::<E>(&bytes, g1_len + g2_len, compressed, false)?;
+        let delta = read_g2::<E>(&bytes, g1_len + 2 * g2_len, compressed, false)?;
 
-        let alpha = G1Affine::from_array(
-            &bytes.env(),
-            &bytes
-                .slice(0..96)
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG1Length)?,
-        );
-        let beta = G2Affine::from_array(
-            &bytes.env(),
-            &bytes
-                .slice(96..288)
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG2Length)?,
-        );
-        let gamma = G2Affine::from_array(
-            &bytes.env(),
-            &bytes
-                .slice(288..480)
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG2Length)?,
-        );
-        let delta = G2Affine::from_array(
-            &bytes.env(),
-            &bytes
-                .slice(480..672)
-                .try_into()
-                .map_err(|_| Groth16Error::InvalidG2Length)?,
-        );
-
-        let mut ic: Vec<G1Affine> = vec![
-            &bytes.env(),
-            G1Affine::from_array(
-                &bytes.env(),
-                &bytes
-                    .slice(672..768)
-                    .try_into()
-                    .map_err(|_| Groth16Error::InvalidG1Length)?,
-            ),
-        ];
-        for i in 0..public_inputs_len {
-            ic.push_back(G1Affine::from_array(
-                &bytes.env(),
-                &bytes
-                    .slice(672 + (i + 1) * 96..768 + (i + 1) * 96)
-                    .try_into()
-                    .map_err(|_| Groth16Error::InvalidG1Length)?,
-            ));
+        let mut ic = Vec::new(bytes.env());
+        for i in 0..n_ic {
+            let offset = g1_len + 3 * g2_len + i * g1_len;
+            ic.push_back(read_g1::<E>(&bytes, offset, compressed, true)?);
         }
 
         Ok(VerifyingKey {
@@ -115,46 +144,134 @@ impl VerifyingKey {
             gamma_abc_g1: ic,
         })
     }
+
+    /// Canonical encoding of the key: the uncompressed points
+    /// alpha || beta || gamma || delta || gamma_abc_g1[0..=n], which is the
+    /// uncompressed layout `try_from_bytes` accepts.
+    pub fn to_bytes(&self, env: &Env) -> Bytes {
+        let mut bytes = Bytes::new(env);
+        bytes.append(&E::g1_to_bytes(&self.alpha_g1));
+        bytes.append(&E::g2_to_bytes(&self.beta_g2));
+        bytes.append(&E::g2_to_bytes(&self.gamma_g2));
+        bytes.append(&E::g2_to_bytes(&self.delta_g2));
+        for p in self.gamma_abc_g1.iter() {
+            bytes.append(&E::g1_to_bytes(&p));
+        }
+        bytes
+    }
+}
+
+/// Content-addressed ID of a verifying key: sha256 of its canonical encoding.
+/// Off-chain this is simply `sha256(vk_bytes)` over the uncompressed layout;
+/// a key registered in the compressed layout gets the same ID.
+pub fn vk_hash<E: PairingBackend>(env: &Env, vk: &VerifyingKey<E>) -> BytesN<32> {
+    env.crypto().sha256(&vk.to_bytes(env)).to_bytes()
+}
+
+/// Parses big-endian public inputs with [`PairingBackend::fr_from_bytes`].
+pub fn to_field_elements<E: PairingBackend>(
+    env: &Env,
+    public_inputs: Vec<BytesN<32>>,
+) -> Result<Vec<E::Fr>, Groth16Error> {
+    let mut pi = Vec::new(env);
+    for i in public_inputs {
+        pi.push_back(E::fr_from_bytes(i)?);
+    }
+    Ok(pi)
+}
+
+/// Computes `gamma_abc_g1[0] + sum(input_i * gamma_abc_g1[i])` with a single
+/// MSM. A lone input uses `g1_mul`, which is cheaper than an MSM of one.
+pub fn accumulate_inputs<E: PairingBackend>(
+    e: &E,
+    gamma_abc_g1: &Vec<E::G1>,
+    public_inputs: Vec<E::Fr>,
+) -> E::G1 {
+    let ic0 = gamma_abc_g1.get_unchecked(0);
+    let sum = match public_inputs.len() {
+        0 => return ic0,
+        1 => e.g1_mul(
+            &gamma_abc_g1.get_unchecked(1),
+            &public_inputs.get_unchecked(0),
+        ),
+        _ => e.g1_msm(gamma_abc_g1.slice(1..), public_inputs),
+    };
+    e.g1_add(&ic0, &sum)
+}
+
+/// A `VerifyingKey` with the negations the pairing equation needs applied
+/// once, so verification passes the proof points to the host unchanged:
+/// `e(a, b) * e(-alpha, beta) * e(acc, -gamma) * e(c, -delta) == 1`.
+pub struct PreparedVerifyingKey<E: PairingBackend> {
+    pub neg_alpha_g1: E::G1,
+    pub beta_g2: E::G2,
+    pub neg_gamma_g2: E::G2,
+    pub neg_delta_g2: E::G2,
+    pub gamma_abc_g1: Vec<E::G1>,
+}
+
+impl<E: PairingBackend> From<&VerifyingKey<E>> for PreparedVerifyingKey<E> {
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        PreparedVerifyingKey {
+            neg_alpha_g1: E::g1_neg(&vk.alpha_g1),
+            beta_g2: vk.beta_g2.clone(),
+            neg_gamma_g2: E::g2_neg(&vk.gamma_g2),
+            neg_delta_g2: E::g2_neg(&vk.delta_g2),
+            gamma_abc_g1: vk.gamma_abc_g1.clone(),
+        }
+    }
 }
 
-pub fn verify_proof(
-    bls: Bls12_381,
-    vk: &VerifyingKey,
-    proof: &Proof,
-    public_inputs: Vec<Fr>, // field elements
+pub fn verify_proof<E: PairingBackend>(
+    e: &E,
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: Vec<E::Fr>, // field elements
+) -> bool {
+    verify_prepared_proof(e, &vk.into(), proof, public_inputs)
+}
+
+pub fn verify_prepared_proof<E: PairingBackend>(
+    e: &E,
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: Vec<E::Fr>, // field elements
 ) -> bool {
     // 1. Check input length
-    if (public_inputs.len() + 1) != vk.gamma_abc_g1.len() {
+    if (public_inputs.len() + 1) != pvk.gamma_abc_g1.len() {
         return false;
     }
 
     // 2. Compute acc = vk.gamma_abc_g1[0] + sum(input_i * vk.gamma_abc_g1[i])
-    let mut acc: G1Affine = vk.gamma_abc_g1.get_unchecked(0u32);
-    for (i, input) in public_inputs.iter().enumerate() {
-        acc = bls.g1_add(
-            &acc,
-            &bls.g1_mul(&vk.gamma_abc_g1.get_unchecked(i as u32 + 1), &input),
-        );
-    }
-
-    // 3. Compute neg_a = -proof.a
-    // TODO: use ark as a workaround until the native implementation is available
-    let mut neg_a = [0u8; 96];
-    (-G1AffineArk::deserialize_uncompressed(&proof.a.to_array()[..]).unwrap())
-        .serialize_uncompressed(&mut neg_a[..])
-        .unwrap();
+    let acc = accumulate_inputs(e, &pvk.gamma_abc_g1, public_inputs);
 
-    let neg_a = G1Affine::from_array(bls.env(), &neg_a);
+    // 3. Pairing check
+    check_pairing_equation(e, pvk, proof, acc)
+}
 
-    // 4. Pairing check e(-proof.a, proof.b) * e(vk.alpha_g1, vk.beta_g2, vk.gamma_g2) * e(vk.gamma_g2, acc) * e(proof.c, vk.delta_g2) == 1
-    let a = bls.pairing_check(
-        vec![bls.env(), neg_a, vk.alpha_g1.clone(), acc, proof.c.clone()],
+/// Checks `e(proof.a, proof.b) * e(-vk.alpha_g1, vk.beta_g2) *
+/// e(acc, -vk.gamma_g2) * e(proof.c, -vk.delta_g2) == 1` for an already
+/// accumulated `acc`.
+pub fn check_pairing_equation<E: PairingBackend>(
+    e: &E,
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    acc: E::G1,
+) -> bool {
+    let a = e.pairing_check(
         vec![
-            bls.env(),
+            e.env(),
+            proof.a.clone(),
+            pvk.neg_alpha_g1.clone(),
+            acc,
+            proof.c.clone(),
+        ],
+        vec![
+            e.env(),
             proof.b.clone(),
-            vk.beta_g2.clone(),
-            vk.gamma_g2.clone(),
-            vk.delta_g2.clone(),
+            pvk.beta_g2.clone(),
+            pvk.neg_gamma_g2.clone(),
+            pvk.neg_delta_g2.clone(),
         ],
     );
 
@@ -163,10 +280,5 @@ pub fn verify_proof(
         extern crate std;
         std::println!("pairing_check: {:?}", a);
     }
-    return a;
+    a
 }
-
-// pub fn is_less_than_bn254_field_size_be(bytes: &[u8; 32]) -> bool {
-//     let bigint = BigUint::from_bytes_be(bytes);
-//     bigint < ark_bls12_381::Fr::MODULUS.into()
-// }
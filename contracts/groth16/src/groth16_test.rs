@@ -1,3 +1,344 @@
+/// Arkworks-generated proofs in the byte layouts the contract expects, for
+/// circuits with an arbitrary number of public inputs.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    extern crate std;
+
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use ark_groth16::Groth16;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_serialize::CanonicalSerialize;
+    use ark_snark::SNARK;
+    use ark_std::rand::SeedableRng;
+    use soroban_sdk::{Bytes, BytesN, Env, Vec};
+
+    /// Proves knowledge of `a`, `b` such that `c_i = a * (b + i)` for each of
+    /// the `n` public inputs `c_i`.
+    #[derive(Clone)]
+    struct InputsDemoCircuit<F: PrimeField> {
+        n: usize,
+        a: Option<F>,
+        b: Option<F>,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for InputsDemoCircuit<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            for i in 0..self.n {
+                let offset = F::from(i as u64);
+                let c = cs.new_input_variable(|| {
+                    let a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                    let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+                    Ok(a * (b + offset))
+                })?;
+                cs.enforce_constraint(lc!() + a, lc!() + b + (offset, Variable::One), lc!() + c)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Circuit that exposes the given values as public inputs, each bound
+    /// by `x * 1 = x`.
+    #[derive(Clone)]
+    struct PublicInputsCircuit<F: PrimeField> {
+        inputs: std::vec::Vec<F>,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for PublicInputsCircuit<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            for x in self.inputs {
+                let x = cs.new_input_variable(|| Ok(x))?;
+                cs.enforce_constraint(lc!() + x, lc!() + Variable::One, lc!() + x)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn serialize<T: CanonicalSerialize>(t: &T) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![];
+        t.serialize_uncompressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// RFC 9380 `hash_to_field` to one BLS12-381 scalar with
+    /// `expand_message_xmd(SHA-256)`. arkworks 0.4's `DefaultFieldHasher`
+    /// pads the first block to the element length rather than SHA-256's
+    /// block size, so it cannot serve as the reference.
+    pub(crate) fn hash_to_field(msg: &[u8], dst: &[u8]) -> BlsFr {
+        use sha2::{Digest, Sha256};
+
+        let dst_prime = [dst, &[dst.len() as u8]].concat();
+        let b0 = Sha256::digest([&[0u8; 64][..], msg, &[0, 48, 0], &dst_prime].concat());
+        let b1 = Sha256::digest([&b0[..], &[1], &dst_prime].concat());
+        let chained: std::vec::Vec<u8> = b0.iter().zip(b1.iter()).map(|(x, y)| x ^ y).collect();
+        let b2 = Sha256::digest([&chained[..], &[2], &dst_prime].concat());
+        BlsFr::from_be_bytes_mod_order(&[&b1[..], &b2[..16]].concat())
+    }
+
+    pub(crate) fn fr_bytes<F: PrimeField>(env: &Env, fr: &F) -> BytesN<32> {
+        BytesN::from_array(env, &fr.into_bigint().to_bytes_be().try_into().unwrap())
+    }
+
+    /// Returns the verifying key and `n_proofs` (proof, public inputs) pairs
+    /// for a circuit with `n_inputs` public inputs.
+    pub(crate) fn generate(
+        env: &Env,
+        n_inputs: usize,
+        n_proofs: usize,
+        seed: u64,
+    ) -> (Bytes, std::vec::Vec<(BytesN<384>, Vec<BytesN<32>>)>) {
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed);
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(
+            InputsDemoCircuit {
+                n: n_inputs,
+                a: None,
+                b: None,
+            },
+            rng,
+        )
+        .unwrap();
+
+        let mut vk_bytes = Bytes::new(env);
+        vk_bytes.extend_from_slice(&serialize(&vk.alpha_g1));
+        vk_bytes.extend_from_slice(&serialize(&vk.beta_g2));
+        vk_bytes.extend_from_slice(&serialize(&vk.gamma_g2));
+        vk_bytes.extend_from_slice(&serialize(&vk.delta_g2));
+        for p in vk.gamma_abc_g1.iter() {
+            vk_bytes.extend_from_slice(&serialize(p));
+        }
+
+        let mut proofs = std::vec![];
+        for _ in 0..n_proofs {
+            let a = BlsFr::rand(rng);
+            let b = BlsFr::rand(rng);
+            let proof = Groth16::<Bls12_381>::prove(
+                &pk,
+                InputsDemoCircuit {
+                    n: n_inputs,
+                    a: Some(a),
+                    b: Some(b),
+                },
+                rng,
+            )
+            .unwrap();
+
+            let mut inputs = Vec::new(env);
+            for i in 0..n_inputs {
+                inputs.push_back(fr_bytes(env, &(a * (b + BlsFr::from(i as u64)))));
+            }
+            let proof = BytesN::from_array(env, &serialize(&proof).try_into().unwrap());
+            proofs.push((proof, inputs));
+        }
+
+        (vk_bytes, proofs)
+    }
+
+    /// A key, proof and public inputs in the `verify_with_commitments`
+    /// layouts, with one commitment binding the single public input.
+    ///
+    /// The proof is an ordinary proof of the two-input circuit whose second
+    /// input stands in for the commitment hash: the key scales that input's
+    /// point by `input / hash` and subtracts the commitment from
+    /// `gamma_abc_g1[0]`, so the verifier's accumulator is unchanged.
+    pub(crate) fn generate_with_commitment(
+        env: &Env,
+        seed: u64,
+    ) -> (Bytes, Bytes, Vec<BytesN<32>>) {
+        use ark_bls12_381::{G1Projective, G2Projective};
+        use ark_ec::{CurveGroup, Group};
+        use ark_ff::Field;
+        use ark_groth16::{Proof, VerifyingKey};
+        use ark_serialize::CanonicalDeserialize;
+
+        let (vk_bytes, proofs) = generate(env, 2, 1, seed);
+        let vk_bytes = vk_bytes.to_alloc_vec();
+        let vk = VerifyingKey::<Bls12_381>::deserialize_uncompressed(
+            &[&vk_bytes[..672], &3u64.to_le_bytes(), &vk_bytes[672..]].concat()[..],
+        )
+        .unwrap();
+        let (proof, inputs) = &proofs[0];
+        let proof = Proof::<Bls12_381>::deserialize_uncompressed(&proof.to_array()[..]).unwrap();
+        let input = inputs.get_unchecked(0).to_array();
+        let hashed_input = BlsFr::from_be_bytes_mod_order(&inputs.get_unchecked(1).to_array());
+
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed);
+        let sigma = BlsFr::rand(rng);
+        let commitment = (G1Projective::generator() * BlsFr::rand(rng)).into_affine();
+        let pok = (commitment * sigma).into_affine();
+        let g = (G2Projective::generator() * BlsFr::rand(rng)).into_affine();
+        let g_sigma_neg = (-(g * sigma)).into_affine();
+
+        let hash = hash_to_field(
+            &[&serialize(&commitment)[..], &input].concat(),
+            b"bsb22-commitment",
+        );
+
+        let ic = [
+            (vk.gamma_abc_g1[0] - commitment).into_affine(),
+            vk.gamma_abc_g1[1],
+            (vk.gamma_abc_g1[2] * (hashed_input * hash.inverse().unwrap())).into_affine(),
+        ];
+        let mut key = Bytes::new(env);
+        key.extend_from_slice(&1u32.to_be_bytes());
+        key.extend_from_slice(&serialize(&g));
+        key.extend_from_slice(&serialize(&g_sigma_neg));
+        key.extend_from_slice(&1u32.to_be_bytes());
+        key.extend_from_slice(&1u32.to_be_bytes());
+        key.extend_from_slice(&vk_bytes[..672]);
+        for p in ic.iter() {
+            key.extend_from_slice(&serialize(p));
+        }
+
+        let mut proof_bytes = Bytes::from_slice(env, &serialize(&proof));
+        proof_bytes.extend_from_slice(&1u32.to_be_bytes());
+        proof_bytes.extend_from_slice(&serialize(&commitment));
+        proof_bytes.extend_from_slice(&serialize(&pok));
+
+        let mut public_inputs = Vec::new(env);
+        public_inputs.push_back(inputs.get_unchecked(0));
+        (key, proof_bytes, public_inputs)
+    }
+
+    fn bn254_fq_bytes(fq: &ark_bn254::Fq) -> std::vec::Vec<u8> {
+        fq.into_bigint().to_bytes_be()
+    }
+
+    /// The 64-byte Ethereum encoding of a BN254 G1 point.
+    pub(crate) fn bn254_g1_bytes(p: &ark_bn254::G1Affine) -> [u8; 64] {
+        use ark_ec::AffineRepr;
+
+        let mut out = [0u8; 64];
+        if let Some((x, y)) = p.xy() {
+            out[0..32].copy_from_slice(&bn254_fq_bytes(x));
+            out[32..64].copy_from_slice(&bn254_fq_bytes(y));
+        }
+        out
+    }
+
+    /// The 128-byte Ethereum encoding of a BN254 G2 point, each coordinate
+    /// as `c1 || c0`.
+    pub(crate) fn bn254_g2_bytes(p: &ark_bn254::G2Affine) -> [u8; 128] {
+        use ark_ec::AffineRepr;
+
+        let mut out = [0u8; 128];
+        if let Some((x, y)) = p.xy() {
+            for (i, c) in [x.c1, x.c0, y.c1, y.c0].iter().enumerate() {
+                out[32 * i..32 * (i + 1)].copy_from_slice(&bn254_fq_bytes(c));
+            }
+        }
+        out
+    }
+
+    /// BN254 counterpart of [`generate`], in the `verify_bn254` layouts.
+    pub(crate) fn generate_bn254(
+        env: &Env,
+        n_inputs: usize,
+        n_proofs: usize,
+        seed: u64,
+    ) -> (Bytes, std::vec::Vec<(BytesN<256>, Vec<BytesN<32>>)>) {
+        use ark_bn254::{Bn254, Fr};
+
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed);
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(
+            InputsDemoCircuit::<Fr> {
+                n: n_inputs,
+                a: None,
+                b: None,
+            },
+            rng,
+        )
+        .unwrap();
+
+        let mut vk_bytes = Bytes::new(env);
+        vk_bytes.extend_from_slice(&bn254_g1_bytes(&vk.alpha_g1));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.beta_g2));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.gamma_g2));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.delta_g2));
+        for p in vk.gamma_abc_g1.iter() {
+            vk_bytes.extend_from_slice(&bn254_g1_bytes(p));
+        }
+
+        let mut proofs = std::vec![];
+        for _ in 0..n_proofs {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let proof = Groth16::<Bn254>::prove(
+                &pk,
+                InputsDemoCircuit {
+                    n: n_inputs,
+                    a: Some(a),
+                    b: Some(b),
+                },
+                rng,
+            )
+            .unwrap();
+
+            let mut inputs = Vec::new(env);
+            for i in 0..n_inputs {
+                inputs.push_back(fr_bytes(env, &(a * (b + Fr::from(i as u64)))));
+            }
+            let mut bytes = [0u8; 256];
+            bytes[0..64].copy_from_slice(&bn254_g1_bytes(&proof.a));
+            bytes[64..192].copy_from_slice(&bn254_g2_bytes(&proof.b));
+            bytes[192..256].copy_from_slice(&bn254_g1_bytes(&proof.c));
+            proofs.push((BytesN::from_array(env, &bytes), inputs));
+        }
+
+        (vk_bytes, proofs)
+    }
+
+    /// A stand-in for SP1's Groth16 wrapper: a BN254 key with 2 public
+    /// inputs in the `verify_bn254` layout, and a proof blob carrying
+    /// `selector` for `program_vkey` and `public_values`, with the inputs
+    /// derived independently of the contract.
+    pub(crate) fn generate_sp1(
+        env: &Env,
+        selector: &[u8; 4],
+        program_vkey: &[u8; 32],
+        public_values: &[u8],
+        seed: u64,
+    ) -> (Bytes, Bytes) {
+        use ark_bn254::{Bn254, Fr};
+        use sha2::{Digest, Sha256};
+
+        let mut digest: [u8; 32] = Sha256::digest(public_values).into();
+        digest[0] &= 0x1f;
+        let circuit = PublicInputsCircuit {
+            inputs: std::vec![
+                Fr::from_be_bytes_mod_order(program_vkey),
+                Fr::from_be_bytes_mod_order(&digest),
+            ],
+        };
+
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed);
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, rng).unwrap();
+
+        let mut vk_bytes = std::vec::Vec::new();
+        vk_bytes.extend_from_slice(&bn254_g1_bytes(&vk.alpha_g1));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.beta_g2));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.gamma_g2));
+        vk_bytes.extend_from_slice(&bn254_g2_bytes(&vk.delta_g2));
+        for p in vk.gamma_abc_g1.iter() {
+            vk_bytes.extend_from_slice(&bn254_g1_bytes(p));
+        }
+
+        let mut blob = Bytes::from_array(env, selector);
+        blob.extend_from_slice(&bn254_g1_bytes(&proof.a));
+        blob.extend_from_slice(&bn254_g2_bytes(&proof.b));
+        blob.extend_from_slice(&bn254_g1_bytes(&proof.c));
+
+        (Bytes::from_slice(env, &vk_bytes), blob)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate std;
@@ -200,4 +541,211 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_negation_matches_arkworks() {
+        use crate::bls12_381::{negate_g1, negate_g2};
+        use ark_bls12_381::{G1Affine, G2Affine};
+        use soroban_sdk::{crypto::bls12_381, Env};
+
+        let env = Env::default();
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(0u64);
+        for _ in 0..5 {
+            let p = G1Affine::rand(rng);
+            let mut bytes = [0u8; 96];
+            p.serialize_uncompressed(&mut bytes[..]).unwrap();
+            let mut expected = [0u8; 96];
+            (-p).serialize_uncompressed(&mut expected[..]).unwrap();
+            let neg = negate_g1(&bls12_381::G1Affine::from_array(&env, &bytes));
+            assert_eq!(neg.to_array(), expected);
+
+            let q = G2Affine::rand(rng);
+            let mut bytes = [0u8; 192];
+            q.serialize_uncompressed(&mut bytes[..]).unwrap();
+            let mut expected = [0u8; 192];
+            (-q).serialize_uncompressed(&mut expected[..]).unwrap();
+            let neg = negate_g2(&bls12_381::G2Affine::from_array(&env, &bytes));
+            assert_eq!(neg.to_array(), expected);
+        }
+
+        let mut zero = [0u8; 96];
+        G1Affine::zero()
+            .serialize_uncompressed(&mut zero[..])
+            .unwrap();
+        let neg = negate_g1(&bls12_381::G1Affine::from_array(&env, &zero));
+        assert_eq!(neg.to_array(), zero);
+    }
+
+    #[test]
+    fn test_proof_points_are_validated() {
+        use crate::bls12_381::Proof;
+        use crate::errors::Groth16Error;
+        use ark_bls12_381::{Fq, Fq2, G1Affine, G2Affine};
+        use soroban_sdk::{Bytes, Env};
+
+        let env = Env::default();
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(0u64);
+        let mut valid = [0u8; 384];
+        G1Affine::rand(rng)
+            .serialize_uncompressed(&mut valid[0..96])
+            .unwrap();
+        G2Affine::rand(rng)
+            .serialize_uncompressed(&mut valid[96..288])
+            .unwrap();
+        G1Affine::rand(rng)
+            .serialize_uncompressed(&mut valid[288..384])
+            .unwrap();
+        let parse =
+            |bytes: &[u8; 384]| Proof::try_from_bytes(Bytes::from_array(&env, bytes)).map(|_| ());
+        assert_eq!(parse(&valid), Ok(()));
+
+        let mut identity = valid;
+        identity[288..384].fill(0);
+        identity[288] = 0x40;
+        assert_eq!(parse(&identity), Err(Groth16Error::PointAtInfinity));
+
+        let mut compressed = valid;
+        compressed[96] |= 0x80;
+        assert_eq!(parse(&compressed), Err(Groth16Error::DecompressingG2Failed));
+
+        let mut off_curve = valid;
+        off_curve[95] ^= 1;
+        assert_eq!(parse(&off_curve), Err(Groth16Error::PointNotOnCurve));
+        let mut off_curve = valid;
+        off_curve[287] ^= 1;
+        assert_eq!(parse(&off_curve), Err(Groth16Error::PointNotOnCurve));
+
+        // Points on the curve but outside the prime-order subgroup.
+        let g1 = (1u64..)
+            .filter_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), true))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let mut small_order = valid;
+        g1.serialize_uncompressed(&mut small_order[0..96]).unwrap();
+        assert_eq!(parse(&small_order), Err(Groth16Error::PointNotInSubgroup));
+
+        let g2 = (1u64..)
+            .filter_map(|x| {
+                G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(x), Fq::from(1u64)), true)
+            })
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        let mut small_order = valid;
+        g2.serialize_uncompressed(&mut small_order[96..288])
+            .unwrap();
+        assert_eq!(parse(&small_order), Err(Groth16Error::PointNotInSubgroup));
+    }
+
+    #[test]
+    fn test_expand_message_xmd_matches_rfc9380() {
+        use crate::bls12_381::expand_message_xmd;
+        use soroban_sdk::{Bytes, Env};
+
+        // RFC 9380 appendix K.1, expand_message_xmd(SHA-256).
+        let env = Env::default();
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let cases: [(&[u8], &str); 2] = [
+            (
+                b"",
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                b"abc",
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+        ];
+        for (msg, expected) in cases {
+            let out = expand_message_xmd(&env, &Bytes::from_slice(&env, msg), dst, 32);
+            assert_eq!(hex::encode(out.to_alloc_vec()), expected);
+        }
+    }
+
+    #[test]
+    fn test_hash_to_fr_matches_reference() {
+        use super::fixtures::hash_to_field;
+        use crate::bls12_381::hash_to_fr;
+        use soroban_sdk::{Bytes, Env};
+
+        let env = Env::default();
+        for len in [0usize, 1, 96, 200] {
+            let msg: vec::Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            let expected = hash_to_field(&msg, b"bsb22-commitment");
+            let got = hash_to_fr(
+                &env.crypto().bls12_381(),
+                &Bytes::from_slice(&env, &msg),
+                b"bsb22-commitment",
+            );
+            assert_eq!(
+                got.to_bytes().to_array().to_vec(),
+                expected.into_bigint().to_bytes_be()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decompression_matches_arkworks() {
+        use crate::curve::{g1_decompress, g2_decompress};
+        use ark_bls12_381::{G1Affine, G2Affine};
+
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(0u64);
+        for _ in 0..10 {
+            let p = G1Affine::rand(rng);
+            let mut compressed = [0u8; 48];
+            p.serialize_compressed(&mut compressed[..]).unwrap();
+            let mut expected = [0u8; 96];
+            p.serialize_uncompressed(&mut expected[..]).unwrap();
+            assert_eq!(g1_decompress(&compressed), Some(expected));
+
+            let q = G2Affine::rand(rng);
+            let mut compressed = [0u8; 96];
+            q.serialize_compressed(&mut compressed[..]).unwrap();
+            let mut expected = [0u8; 192];
+            q.serialize_uncompressed(&mut expected[..]).unwrap();
+            assert_eq!(g2_decompress(&compressed), Some(expected));
+        }
+
+        let mut compressed = [0u8; 48];
+        G1Affine::zero()
+            .serialize_compressed(&mut compressed[..])
+            .unwrap();
+        let mut expected = [0u8; 96];
+        G1Affine::zero()
+            .serialize_uncompressed(&mut expected[..])
+            .unwrap();
+        assert_eq!(g1_decompress(&compressed), Some(expected));
+
+        // Missing compression flag, infinity with a sort flag, x >= p.
+        let mut bad = compressed;
+        bad[0] = 0;
+        assert_eq!(g1_decompress(&bad), None);
+        bad[0] = 0xe0;
+        assert_eq!(g1_decompress(&bad), None);
+        let bad = [0xffu8; 48];
+        assert_eq!(g1_decompress(&bad), None);
+    }
+
+    #[test]
+    fn test_bn254_g2_subgroup_check_matches_arkworks() {
+        use super::fixtures::bn254_g2_bytes;
+        use crate::curve_bn254::g2_is_in_subgroup;
+        use ark_bn254::{Fq, Fq2, G2Affine};
+
+        let rng = &mut ark_std::rand::rngs::StdRng::seed_from_u64(0u64);
+        for _ in 0..10 {
+            assert!(g2_is_in_subgroup(&bn254_g2_bytes(&G2Affine::rand(rng))));
+        }
+
+        let mut outside = 0;
+        for x in 1u64..40 {
+            let Some(p) =
+                G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(x), Fq::from(0)), false)
+            else {
+                continue;
+            };
+            let expected = p.is_in_correct_subgroup_assuming_on_curve();
+            assert_eq!(g2_is_in_subgroup(&bn254_g2_bytes(&p)), expected);
+            outside += usize::from(!expected);
+        }
+        assert!(outside > 0);
+    }
 }
@@ -1,37 +1,631 @@
 #![no_std]
+extern crate alloc;
+
+pub mod bls12_381;
+pub mod bn254;
+mod curve;
+mod curve_bn254;
 pub mod errors;
+mod events;
 pub mod groth16;
+pub mod pairing;
+pub mod sp1;
+mod storage;
 
+use bls12_381::{
+    verify_committed_proof, verify_proofs_batch, BatchVerifier, CommittedProof,
+    CommittedVerifyingKey, PreparedVerifyingKey, Proof, VerifyingKey,
+};
 use errors::Groth16Error;
-use groth16::{verify_proof, Proof, VerifyingKey};
-use soroban_sdk::{contract, contractimpl, crypto::bls12_381::Fr, Bytes, BytesN, Env, Vec};
+use events::{
+    CircuitVkCancelled, CircuitVkExecuted, CircuitVkProposed, DeprecatedVkUsed, VkDeprecated,
+    VkRevoked,
+};
+use groth16::{to_field_elements, verify_prepared_proof, vk_hash};
+use soroban_sdk::{
+    contract, contractimpl,
+    crypto::{
+        bls12_381::{Bls12_381, Fr},
+        bn254::Bn254,
+    },
+    Address, Bytes, BytesN, Env, Symbol, Vec, U256,
+};
+use storage::{PendingVkRecord, StoredVk, VkRecord};
+
+pub use storage::{PendingVkInfo, TtlConfig, VkInfo, VkStatus};
+
+/// `verify_many` status of an entry that verified.
+pub const STATUS_OK: u32 = 0;
+
+/// `verify_many` status of an entry whose key, proof or public inputs could
+/// not be parsed or failed validation. Above every `Groth16Error` code.
+pub const STATUS_MALFORMED: u32 = u32::MAX;
 
 #[contract]
 pub struct Groth16Contract;
 
 #[contractimpl]
 impl Groth16Contract {
+    /// `upgrade_delay` is the number of ledgers a proposed circuit key
+    /// replacement must wait before it can be executed.
+    pub fn __constructor(env: Env, admin: Address, upgrade_delay: u32) {
+        storage::write_admin(&env, &admin);
+        storage::write_upgrade_delay(&env, upgrade_delay);
+        storage::extend_instance(&env);
+    }
+
+    pub fn admin(env: Env) -> Address {
+        storage::read_admin(&env)
+    }
+
+    /// First step of an admin transfer: the current admin nominates
+    /// `new_admin`, who must then call `accept_admin`.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        storage::read_admin(&env).require_auth();
+        storage::write_pending_admin(&env, &new_admin);
+    }
+
+    pub fn accept_admin(env: Env) -> Result<(), Groth16Error> {
+        let new_admin = storage::read_pending_admin(&env).ok_or(Groth16Error::NoPendingAdmin)?;
+        new_admin.require_auth();
+        storage::write_admin(&env, &new_admin);
+        storage::remove_pending_admin(&env);
+
+        Ok(())
+    }
+
     pub fn verify(
         env: Env,
         proof: BytesN<384>,
         vk: Bytes,
         public_inputs: Vec<BytesN<32>>,
     ) -> Result<(), Groth16Error> {
-        let proof = Proof::try_from_bytes(proof)?;
         let vk = VerifyingKey::try_from_bytes(vk, public_inputs.len())?;
-        let mut pi = Vec::new(&env);
-        for i in public_inputs {
-            let i = Fr::from_bytes(i);
-            pi.push_back(i);
+        let proof = Proof::try_from_bytes(proof.into())?;
+        verify_with_vk(&env, &(&vk).into(), proof, public_inputs)
+    }
+
+    /// Same as `verify`, for a key serialized by arkworks, compressed or
+    /// not. Arkworks' uncompressed proof encoding is already the `proof`
+    /// layout.
+    pub fn verify_ark(
+        env: Env,
+        proof: BytesN<384>,
+        vk: Bytes,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let vk = VerifyingKey::try_from_ark_bytes(vk, public_inputs.len())?;
+        let proof = Proof::try_from_bytes(proof.into())?;
+        verify_with_vk(&env, &(&vk).into(), proof, public_inputs)
+    }
+
+    /// Same as `verify`, for a proof whose points are compressed in the ZCash
+    /// format: `a || b || c` as 48 + 96 + 48 bytes.
+    pub fn verify_compressed(
+        env: Env,
+        proof: BytesN<192>,
+        vk: Bytes,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let vk = VerifyingKey::try_from_bytes(vk, public_inputs.len())?;
+        let proof = Proof::try_from_compressed_bytes(proof.into())?;
+        verify_with_vk(&env, &(&vk).into(), proof, public_inputs)
+    }
+
+    /// Same as `verify`, for a circuit using gnark's `Commit` API. The proof
+    /// and key carry the Pedersen commitments and commitment keys in the
+    /// layouts of `CommittedProof::try_from_bytes` and
+    /// `CommittedVerifyingKey::try_from_bytes`. `public_inputs` does
+    /// not include the commitment hashes, which are derived here.
+    pub fn verify_with_commitments(
+        env: Env,
+        proof: Bytes,
+        vk: Bytes,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let vk = CommittedVerifyingKey::try_from_bytes(vk, public_inputs.len())?;
+        let proof = CommittedProof::try_from_bytes(proof)?;
+        let pi = to_field_elements::<Bls12_381>(&env, public_inputs)?;
+
+        if !verify_committed_proof(env.crypto().bls12_381(), &vk, &proof, pi) {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        Ok(())
+    }
+
+    /// Same as `verify`, over BN254: a 256-byte `a || b || c` proof, a
+    /// 448 + 64 * (n + 1)-byte key and inputs below the BN254 r. See
+    /// [`bn254`] for the point encodings.
+    pub fn verify_bn254(
+        env: Env,
+        proof: BytesN<256>,
+        vk: Bytes,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let vk = bn254::VerifyingKey::try_from_bytes(vk, public_inputs.len())?;
+        let proof = bn254::Proof::try_from_bytes(proof.into())?;
+        let pi = to_field_elements::<Bn254>(&env, public_inputs)?;
+
+        if !groth16::verify_proof(&env.crypto().bn254(), &vk, &proof, pi) {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+        Ok(())
+    }
+
+    /// Verifies an SP1 Groth16 proof that the program with vkey hash
+    /// `program_vkey` committed `public_values`, under the SP1 Groth16 key
+    /// pinned in [`sp1::GROTH16_PVK`]. See [`sp1`] for the proof layout.
+    pub fn verify_sp1(
+        env: Env,
+        proof: Bytes,
+        program_vkey: BytesN<32>,
+        public_values: Bytes,
+    ) -> Result<(), Groth16Error> {
+        sp1::verify_proof(
+            &env,
+            &sp1::prepared_key(&env),
+            &sp1::GROTH16_VK_SELECTOR,
+            proof,
+            program_vkey,
+            &public_values,
+        )
+    }
+
+    /// Verifies every proof in `proofs` against the matching entry of
+    /// `public_inputs` under the same `vk`, with one randomized pairing
+    /// check. Fails if any proof is invalid.
+    pub fn verify_batch(
+        env: Env,
+        vk: Bytes,
+        proofs: Vec<BytesN<384>>,
+        public_inputs: Vec<Vec<BytesN<32>>>,
+    ) -> Result<(), Groth16Error> {
+        if proofs.is_empty() {
+            return Err(Groth16Error::EmptyBatch);
+        }
+        if proofs.len() != public_inputs.len() {
+            return Err(Groth16Error::InvalidPublicInputsLength);
+        }
+
+        let n_inputs = public_inputs.get_unchecked(0).len();
+        let vk = VerifyingKey::try_from_bytes(vk, n_inputs)?;
+        let mut batch = alloc::vec::Vec::new();
+        let mut coeffs = Vec::new(&env);
+        for (proof, inputs) in proofs.iter().zip(public_inputs.iter()) {
+            if inputs.len() != n_inputs {
+                return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
+            }
+            batch.push((
+                Proof::try_from_bytes(proof.into())?,
+                to_field_elements::<Bls12_381>(&env, inputs)?,
+            ));
+            coeffs.push_back(random_coefficient(&env));
         }
 
         let bls = env.crypto().bls12_381();
-        if !verify_proof(bls, &vk, &proof, pi) {
+        if !verify_proofs_batch(bls, &(&vk).into(), &batch, coeffs) {
+            return Err(Groth16Error::ProofVerificationFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a set of `(vk, proof, public_inputs)` entries, possibly for
+    /// different circuits, with one randomized pairing check. Entries that
+    /// share a key share its pairings. Fails if any entry is invalid.
+    pub fn verify_multi(
+        env: Env,
+        entries: Vec<(Bytes, BytesN<384>, Vec<BytesN<32>>)>,
+    ) -> Result<(), Groth16Error> {
+        if entries.is_empty() {
+            return Err(Groth16Error::EmptyBatch);
+        }
+
+        let mut groups: alloc::vec::Vec<KeyGroup> = alloc::vec::Vec::new();
+        for (vk, proof, inputs) in entries.iter() {
+            let n_inputs = inputs.len();
+            let entry = (
+                Proof::try_from_bytes(proof.into())?,
+                to_field_elements::<Bls12_381>(&env, inputs)?,
+            );
+            let r = random_coefficient(&env);
+
+            match groups.iter_mut().find(|group| group.vk_bytes == vk) {
+                Some(group) => {
+                    if group.pvk.gamma_abc_g1.len() != n_inputs + 1 {
+                        return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
+                    }
+                    group.proofs.push(entry);
+                    group.coeffs.push_back(r);
+                }
+                None => groups.push(KeyGroup {
+                    pvk: (&VerifyingKey::try_from_bytes(vk.clone(), n_inputs)?).into(),
+                    vk_bytes: vk,
+                    proofs: alloc::vec![entry],
+                    coeffs: soroban_sdk::vec![&env, r],
+                }),
+            }
+        }
+
+        let mut batch = BatchVerifier::new(env.crypto().bls12_381());
+        for group in groups {
+            if !batch.add(&group.pvk, &group.proofs, group.coeffs) {
+                return Err(Groth16Error::ProofVerificationFailed);
+            }
+        }
+        if !batch.verify() {
             return Err(Groth16Error::ProofVerificationFailed);
         }
 
         Ok(())
     }
+
+    /// Verifies each `(vk, proof, public_inputs)` entry on its own and never
+    /// fails: returns one status per entry, `STATUS_OK`, `STATUS_MALFORMED`
+    /// or the `Groth16Error` code of a well-formed entry that did not
+    /// verify.
+    pub fn verify_many(env: Env, entries: Vec<(Bytes, BytesN<384>, Vec<BytesN<32>>)>) -> Vec<u32> {
+        let mut statuses = Vec::new(&env);
+        for (vk, proof, inputs) in entries.iter() {
+            statuses.push_back(entry_status(&env, vk, proof, inputs));
+        }
+        statuses
+    }
+
+    /// Parses `vk` for a circuit with `n_inputs` public inputs and stores it
+    /// in persistent storage as an active key tagged with `version`. Returns
+    /// the ID to pass to `verify_with_id`, which is the `vk_hash` of the key,
+    /// so registering the same key twice returns the same ID and keeps the
+    /// original record.
+    pub fn register_vk(
+        env: Env,
+        vk: Bytes,
+        n_inputs: u32,
+        version: u32,
+    ) -> Result<BytesN<32>, Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        let vk = VerifyingKey::try_from_bytes(vk, n_inputs)?;
+        let vk_id = vk_hash(&env, &vk);
+        if !storage::has_vk(&env, &vk_id) {
+            let record = VkRecord {
+                info: VkInfo {
+                    version,
+                    status: VkStatus::Active,
+                    sunset_ledger: None,
+                },
+                vk: StoredVk::from_pvk(&env, &(&vk).into()),
+            };
+            storage::write_vk(&env, &vk_id, &record);
+        }
+
+        Ok(vk_id)
+    }
+
+    pub fn vk_info(env: Env, vk_id: BytesN<32>) -> Result<VkInfo, Groth16Error> {
+        let mut info = storage::read_vk(&env, &vk_id)
+            .ok_or(Groth16Error::VerifyingKeyNotFound)?
+            .info;
+        info.status = info.effective_status(&env);
+
+        Ok(info)
+    }
+
+    /// Marks a key as deprecated. It keeps verifying until `sunset_ledger`,
+    /// if given, after which it behaves as revoked.
+    pub fn deprecate_vk(
+        env: Env,
+        vk_id: BytesN<32>,
+        sunset_ledger: Option<u32>,
+    ) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        let mut record =
+            storage::read_vk(&env, &vk_id).ok_or(Groth16Error::VerifyingKeyNotFound)?;
+        if record.info.effective_status(&env) == VkStatus::Revoked {
+            return Err(Groth16Error::VerifyingKeyRevoked);
+        }
+        record.info.status = VkStatus::Deprecated;
+        record.info.sunset_ledger = sunset_ledger;
+        storage::write_vk(&env, &vk_id, &record);
+        VkDeprecated {
+            vk_id,
+            sunset_ledger,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn revoke_vk(env: Env, vk_id: BytesN<32>) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        let mut record =
+            storage::read_vk(&env, &vk_id).ok_or(Groth16Error::VerifyingKeyNotFound)?;
+        record.info.status = VkStatus::Revoked;
+        storage::write_vk(&env, &vk_id, &record);
+        VkRevoked { vk_id }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Deletes a key. A key that a circuit or a pending change still points
+    /// at cannot be removed.
+    pub fn remove_vk(env: Env, vk_id: BytesN<32>) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        if !storage::has_vk(&env, &vk_id) {
+            return Err(Groth16Error::VerifyingKeyNotFound);
+        }
+        if storage::read_vk_refs(&env, &vk_id) > 0 {
+            return Err(Groth16Error::VerifyingKeyInUse);
+        }
+        storage::remove_vk(&env, &vk_id);
+
+        Ok(())
+    }
+
+    /// Binds a new `circuit` name to a registered key that is not revoked.
+    /// Replacing the key of an existing circuit goes through
+    /// `propose_circuit_vk` instead.
+    pub fn set_circuit(env: Env, circuit: Symbol, vk_id: BytesN<32>) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        if storage::read_circuit(&env, &circuit).is_some() {
+            return Err(Groth16Error::CircuitAlreadyBound);
+        }
+        let record = storage::read_vk(&env, &vk_id).ok_or(Groth16Error::VerifyingKeyNotFound)?;
+        if record.info.effective_status(&env) == VkStatus::Revoked {
+            return Err(Groth16Error::VerifyingKeyRevoked);
+        }
+        storage::write_circuit(&env, &circuit, &vk_id);
+        storage::add_vk_ref(&env, &vk_id);
+
+        Ok(())
+    }
+
+    pub fn upgrade_delay(env: Env) -> u32 {
+        storage::read_upgrade_delay(&env)
+    }
+
+    /// Proposes replacing the key behind `circuit`. The change can be
+    /// executed once `upgrade_delay` ledgers have passed. Fails with
+    /// `InvalidUpgradeDelay` if the delay does not fit in the maximum TTL
+    /// or the ledger sequence.
+    pub fn propose_circuit_vk(
+        env: Env,
+        circuit: Symbol,
+        vk: Bytes,
+        n_inputs: u32,
+        version: u32,
+    ) -> Result<PendingVkInfo, Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        if storage::read_circuit(&env, &circuit).is_none() {
+            return Err(Groth16Error::CircuitNotFound);
+        }
+        if storage::read_pending_vk(&env, &circuit).is_some() {
+            return Err(Groth16Error::PendingChangeExists);
+        }
+
+        // The pending record must not expire before it can be executed.
+        let delay = storage::read_upgrade_delay(&env);
+        if delay >= env.storage().max_ttl() {
+            return Err(Groth16Error::InvalidUpgradeDelay);
+        }
+        let eta_ledger = env
+            .ledger()
+            .sequence()
+            .checked_add(delay)
+            .ok_or(Groth16Error::InvalidUpgradeDelay)?;
+
+        let vk = VerifyingKey::try_from_bytes(vk, n_inputs)?;
+        let info = PendingVkInfo {
+            vk_id: vk_hash(&env, &vk),
+            version,
+            eta_ledger,
+        };
+        let record = PendingVkRecord {
+            info: info.clone(),
+            vk: StoredVk::from_pvk(&env, &(&vk).into()),
+        };
+        storage::write_pending_vk(&env, &circuit, &record);
+        storage::add_vk_ref(&env, &info.vk_id);
+        CircuitVkProposed {
+            circuit,
+            vk_id: info.vk_id.clone(),
+            eta_ledger: info.eta_ledger,
+        }
+        .publish(&env);
+
+        Ok(info)
+    }
+
+    pub fn pending_circuit_vk(env: Env, circuit: Symbol) -> Result<PendingVkInfo, Groth16Error> {
+        storage::read_pending_vk(&env, &circuit)
+            .map(|record| record.info)
+            .ok_or(Groth16Error::PendingChangeNotFound)
+    }
+
+    /// Registers the proposed key, if needed, and binds `circuit` to it. A
+    /// key revoked since the proposal is not bound.
+    pub fn execute_circuit_vk(env: Env, circuit: Symbol) -> Result<BytesN<32>, Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        let pending =
+            storage::read_pending_vk(&env, &circuit).ok_or(Groth16Error::PendingChangeNotFound)?;
+        if env.ledger().sequence() < pending.info.eta_ledger {
+            return Err(Groth16Error::TimelockNotElapsed);
+        }
+
+        let vk_id = pending.info.vk_id;
+        if let Some(record) = storage::read_vk(&env, &vk_id) {
+            // The key may have been revoked since it was proposed.
+            if record.info.effective_status(&env) == VkStatus::Revoked {
+                return Err(Groth16Error::VerifyingKeyRevoked);
+            }
+        } else {
+            let record = VkRecord {
+                info: VkInfo {
+                    version: pending.info.version,
+                    status: VkStatus::Active,
+                    sunset_ledger: None,
+                },
+                vk: pending.vk,
+            };
+            storage::write_vk(&env, &vk_id, &record);
+        }
+        // The pending change's reference to the new key becomes the
+        // circuit's.
+        if let Some(old_vk_id) = storage::read_circuit(&env, &circuit) {
+            storage::release_vk_ref(&env, &old_vk_id);
+        }
+        storage::write_circuit(&env, &circuit, &vk_id);
+        storage::remove_pending_vk(&env, &circuit);
+        CircuitVkExecuted {
+            circuit,
+            vk_id: vk_id.clone(),
+        }
+        .publish(&env);
+
+        Ok(vk_id)
+    }
+
+    pub fn cancel_circuit_vk(env: Env, circuit: Symbol) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        let pending =
+            storage::read_pending_vk(&env, &circuit).ok_or(Groth16Error::PendingChangeNotFound)?;
+        storage::remove_pending_vk(&env, &circuit);
+        storage::release_vk_ref(&env, &pending.info.vk_id);
+        CircuitVkCancelled {
+            circuit,
+            vk_id: pending.info.vk_id,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        storage::read_ttl_config(&env)
+    }
+
+    pub fn set_ttl_config(env: Env, config: TtlConfig) -> Result<(), Groth16Error> {
+        storage::read_admin(&env).require_auth();
+        if config.threshold > config.extend_to || config.extend_to > env.storage().max_ttl() {
+            return Err(Groth16Error::InvalidTtlConfig);
+        }
+        storage::write_ttl_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Extends the TTL of a registered key. Anyone may call this to keep a
+    /// key they depend on from being archived.
+    pub fn bump_vk(env: Env, vk_id: BytesN<32>) -> Result<(), Groth16Error> {
+        if !storage::has_vk(&env, &vk_id) {
+            return Err(Groth16Error::VerifyingKeyNotFound);
+        }
+        storage::extend_vk(&env, &vk_id);
+        storage::extend_instance(&env);
+
+        Ok(())
+    }
+
+    pub fn circuit_vk(env: Env, circuit: Symbol) -> Result<BytesN<32>, Groth16Error> {
+        storage::read_circuit(&env, &circuit).ok_or(Groth16Error::CircuitNotFound)
+    }
+
+    pub fn verify_circuit(
+        env: Env,
+        circuit: Symbol,
+        proof: BytesN<384>,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let vk_id = storage::read_circuit(&env, &circuit).ok_or(Groth16Error::CircuitNotFound)?;
+        storage::extend_circuit(&env, &circuit);
+        storage::extend_vk_refs(&env, &vk_id);
+        Self::verify_with_id(env, vk_id, proof, public_inputs)
+    }
+
+    pub fn verify_with_id(
+        env: Env,
+        vk_id: BytesN<32>,
+        proof: BytesN<384>,
+        public_inputs: Vec<BytesN<32>>,
+    ) -> Result<(), Groth16Error> {
+        let record = storage::read_vk(&env, &vk_id).ok_or(Groth16Error::VerifyingKeyNotFound)?;
+        storage::extend_vk(&env, &vk_id);
+        storage::extend_instance(&env);
+        let status = record.info.effective_status(&env);
+        if status == VkStatus::Revoked {
+            return Err(Groth16Error::VerifyingKeyRevoked);
+        }
+
+        let pvk = record.vk.into_pvk(&env);
+        if pvk.gamma_abc_g1.len() != public_inputs.len() + 1 {
+            return Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs);
+        }
+        verify_with_vk(
+            &env,
+            &pvk,
+            Proof::try_from_bytes(proof.into())?,
+            public_inputs,
+        )?;
+
+        if status == VkStatus::Deprecated {
+            DeprecatedVkUsed {
+                vk_id,
+                version: record.info.version,
+                sunset_ledger: record.info.sunset_ledger,
+            }
+            .publish(&env);
+        }
+
+        Ok(())
+    }
+}
+
+fn verify_with_vk(
+    env: &Env,
+    pvk: &PreparedVerifyingKey,
+    proof: Proof,
+    public_inputs: Vec<BytesN<32>>,
+) -> Result<(), Groth16Error> {
+    let pi = to_field_elements::<Bls12_381>(env, public_inputs)?;
+
+    let bls = env.crypto().bls12_381();
+    if !verify_prepared_proof(&bls, pvk, &proof, pi) {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+
+    Ok(())
+}
+
+/// Status of one `verify_many` entry.
+fn entry_status(env: &Env, vk: Bytes, proof: BytesN<384>, public_inputs: Vec<BytesN<32>>) -> u32 {
+    let parsed = VerifyingKey::try_from_bytes(vk, public_inputs.len()).and_then(|vk| {
+        let proof = Proof::try_from_bytes(proof.into())?;
+        let pi = to_field_elements::<Bls12_381>(env, public_inputs)?;
+        Ok((vk, proof, pi))
+    });
+    let Ok((vk, proof, pi)) = parsed else {
+        return STATUS_MALFORMED;
+    };
+
+    let bls = env.crypto().bls12_381();
+    if verify_prepared_proof(&bls, &(&vk).into(), &proof, pi) {
+        STATUS_OK
+    } else {
+        Groth16Error::ProofVerificationFailed as u32
+    }
+}
+
+/// Entries of a `verify_multi` call that share the same key.
+struct KeyGroup {
+    vk_bytes: Bytes,
+    pvk: PreparedVerifyingKey,
+    proofs: alloc::vec::Vec<(Proof, Vec<Fr>)>,
+    coeffs: Vec<Fr>,
+}
+
+/// 128-bit batching coefficient drawn from the ledger PRNG.
+fn random_coefficient(env: &Env) -> Fr {
+    let prng = env.prng();
+    Fr::from_u256(U256::from_parts(env, 0, 0, prng.gen(), prng.gen()))
 }
 
 #[cfg(test)]
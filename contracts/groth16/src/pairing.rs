@@ -0,0 +1,90 @@
+//! The curve operations the Groth16 verifier needs, so that
+//! [`crate::groth16`] is written once for every curve with pairing host
+//! functions. A backend wraps the host functions of one curve.
+
+use soroban_sdk::{Bytes, BytesN, Env, IntoVal, TryFromVal, Val, Vec};
+
+use crate::errors::Groth16Error;
+
+pub trait PairingBackend {
+    type G1: Clone + IntoVal<Env, Val> + TryFromVal<Env, Val>;
+    type G2: Clone + IntoVal<Env, Val> + TryFromVal<Env, Val>;
+    type Fr: Clone + IntoVal<Env, Val> + TryFromVal<Env, Val>;
+
+    /// Lengths of the uncompressed G1 and G2 encodings.
+    const G1_LEN: u32;
+    const G2_LEN: u32;
+
+    /// Lengths of the compressed G1 and G2 encodings, if the backend can
+    /// decompress points.
+    const COMPRESSED_LENS: Option<(u32, u32)> = None;
+
+    fn env(&self) -> &Env;
+
+    /// Decodes an uncompressed G1 point of exactly `G1_LEN` bytes, checking
+    /// that the encoding is canonical and that the point is on the curve and
+    /// in the prime-order subgroup. The identity is rejected unless
+    /// `allow_identity` is set.
+    fn g1_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<Self::G1, Groth16Error>;
+
+    /// G2 counterpart of [`Self::g1_from_bytes`].
+    fn g2_from_bytes(
+        env: &Env,
+        bytes: &Bytes,
+        allow_identity: bool,
+    ) -> Result<Self::G2, Groth16Error>;
+
+    /// Decompresses a G1 point, then validates it like
+    /// [`Self::g1_from_bytes`].
+    fn g1_from_compressed(
+        _env: &Env,
+        _bytes: &Bytes,
+        _allow_identity: bool,
+    ) -> Result<Self::G1, Groth16Error> {
+        Err(Groth16Error::DecompressingG1Failed)
+    }
+
+    /// G2 counterpart of [`Self::g1_from_compressed`].
+    fn g2_from_compressed(
+        _env: &Env,
+        _bytes: &Bytes,
+        _allow_identity: bool,
+    ) -> Result<Self::G2, Groth16Error> {
+        Err(Groth16Error::DecompressingG2Failed)
+    }
+
+    /// Uncompressed encodings, as [`Self::g1_from_bytes`] and
+    /// [`Self::g2_from_bytes`] read them.
+    fn g1_to_bytes(p: &Self::G1) -> Bytes;
+    fn g2_to_bytes(p: &Self::G2) -> Bytes;
+
+    /// Parses a big-endian public input, rejecting values not below r. The
+    /// host reduces modulo r, so without this check `x` and `x + r` would be
+    /// accepted as the same input.
+    fn fr_from_bytes(bytes: BytesN<32>) -> Result<Self::Fr, Groth16Error>;
+
+    /// Negations work on the encodings, without a host call.
+    fn g1_neg(p: &Self::G1) -> Self::G1;
+    fn g2_neg(p: &Self::G2) -> Self::G2;
+
+    fn g1_add(&self, p: &Self::G1, q: &Self::G1) -> Self::G1;
+    fn g1_mul(&self, p: &Self::G1, scalar: &Self::Fr) -> Self::G1;
+
+    /// `sum(scalars_i * points_i)` over at least one point. Backends without
+    /// an MSM host function fall back to a `g1_mul` and a `g1_add` per point.
+    fn g1_msm(&self, points: Vec<Self::G1>, scalars: Vec<Self::Fr>) -> Self::G1 {
+        let mut acc = self.g1_mul(&points.get_unchecked(0), &scalars.get_unchecked(0));
+        for i in 1..points.len() {
+            let term = self.g1_mul(&points.get_unchecked(i), &scalars.get_unchecked(i));
+            acc = self.g1_add(&acc, &term);
+        }
+        acc
+    }
+
+    /// Whether `prod(e(g1_i, g2_i)) == 1`.
+    fn pairing_check(&self, g1: Vec<Self::G1>, g2: Vec<Self::G2>) -> bool;
+}
@@ -0,0 +1,132 @@
+//! SP1 zkVM Groth16 proofs, checked as SP1's own `Groth16Verifier` does. A
+//! proof blob is a 4-byte selector, the first bytes of sha256 of SP1's
+//! `groth16_vk.bin`, followed by the 256-byte BN254 `a || b || c`. The
+//! wrapper circuit has two public inputs: the program vkey hash and the
+//! digest of the program's public values.
+
+use soroban_sdk::{
+    crypto::bn254::{Bn254, Bn254G1Affine, Bn254G2Affine},
+    vec, Bytes, BytesN, Env, Vec,
+};
+
+use crate::{bn254, errors::Groth16Error, groth16};
+
+/// Length of an SP1 Groth16 proof blob.
+pub const PROOF_LEN: u32 = 4 + 256;
+
+/// The selector of SP1 v5's Groth16 proofs, the first 4 bytes of sha256 of
+/// its `groth16_vk.bin`.
+pub const GROTH16_VK_SELECTOR: [u8; 4] = [0xa4, 0x59, 0x4c, 0x59];
+
+/// SP1 v5's Groth16 verifying key, decoded from its `groth16_vk.bin` and
+/// prepared: `-alpha`, `beta`, `-gamma`, `-delta` and the 3 points of `K`,
+/// in the `verify_bn254` point encoding. Pinned here so `verify_sp1` neither
+/// parses nor validates it.
+pub const GROTH16_PVK: [u8; 640] = [
+    0x2d, 0x4d, 0x9a, 0xa7, 0xe3, 0x02, 0xd9, 0xdf, 0x41, 0x74, 0x9d, 0x55, 0x07, 0x94, 0x9d, 0x05,
+    0xdb, 0xea, 0x33, 0xfb, 0xb1, 0x6c, 0x64, 0x3b, 0x22, 0xf5, 0x99, 0xa2, 0xbe, 0x6d, 0xf2, 0xe2,
+    0x1b, 0xa5, 0x71, 0x22, 0xa4, 0xf9, 0xd1, 0x79, 0x56, 0x77, 0x59, 0x56, 0x60, 0xe1, 0x75, 0x17,
+    0xc8, 0xf7, 0xe7, 0x87, 0x4f, 0x4e, 0xc7, 0x8b, 0x4b, 0xa9, 0xc1, 0x17, 0xd8, 0x2f, 0xe4, 0x21,
+    0x09, 0x67, 0x03, 0x2f, 0xcb, 0xf7, 0x76, 0xd1, 0xaf, 0xc9, 0x85, 0xf8, 0x88, 0x77, 0xf1, 0x82,
+    0xd3, 0x84, 0x80, 0xa6, 0x53, 0xf2, 0xde, 0xca, 0xa9, 0x79, 0x4c, 0xbc, 0x3b, 0xf3, 0x06, 0x0c,
+    0x0e, 0x18, 0x78, 0x47, 0xad, 0x4c, 0x79, 0x83, 0x74, 0xd0, 0xd6, 0x73, 0x2b, 0xf5, 0x01, 0x84,
+    0x7d, 0xd6, 0x8b, 0xc0, 0xe0, 0x71, 0x24, 0x1e, 0x02, 0x13, 0xbc, 0x7f, 0xc1, 0x3d, 0xb7, 0xab,
+    0x30, 0x4c, 0xfb, 0xd1, 0xe0, 0x8a, 0x70, 0x4a, 0x99, 0xf5, 0xe8, 0x47, 0xd9, 0x3f, 0x8c, 0x3c,
+    0xaa, 0xfd, 0xde, 0xc4, 0x6b, 0x7a, 0x0d, 0x37, 0x9d, 0xa6, 0x9a, 0x4d, 0x11, 0x23, 0x46, 0xa7,
+    0x17, 0x39, 0xc1, 0xb1, 0xa4, 0x57, 0xa8, 0xc7, 0x31, 0x31, 0x23, 0xd2, 0x4d, 0x2f, 0x91, 0x92,
+    0xf8, 0x96, 0xb7, 0xc6, 0x3e, 0xea, 0x05, 0xa9, 0xd5, 0x7f, 0x06, 0x54, 0x7a, 0xd0, 0xce, 0xc8,
+    0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d, 0x25,
+    0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3, 0x12, 0xc2,
+    0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79,
+    0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed,
+    0x27, 0x5d, 0xc4, 0xa2, 0x88, 0xd1, 0xaf, 0xb3, 0xcb, 0xb1, 0xac, 0x09, 0x18, 0x75, 0x24, 0xc7,
+    0xdb, 0x36, 0x39, 0x5d, 0xf7, 0xbe, 0x3b, 0x99, 0xe6, 0x73, 0xb1, 0x3a, 0x07, 0x5a, 0x65, 0xec,
+    0x1d, 0x9b, 0xef, 0xcd, 0x05, 0xa5, 0x32, 0x3e, 0x6d, 0xa4, 0xd4, 0x35, 0xf3, 0xb6, 0x17, 0xcd,
+    0xb3, 0xaf, 0x83, 0x28, 0x5c, 0x2d, 0xf7, 0x11, 0xef, 0x39, 0xc0, 0x15, 0x71, 0x82, 0x7f, 0x9d,
+    0x1c, 0xc7, 0xcb, 0x8d, 0xe7, 0x15, 0x67, 0x5f, 0x21, 0xf0, 0x1e, 0xcc, 0x9b, 0x46, 0xd2, 0x36,
+    0xe0, 0x86, 0x5e, 0x0c, 0xc0, 0x20, 0x02, 0x45, 0x21, 0x99, 0x82, 0x69, 0x84, 0x5f, 0x74, 0xe6,
+    0x03, 0xff, 0x41, 0xf4, 0xba, 0x0c, 0x37, 0xfe, 0x2c, 0xaf, 0x27, 0x35, 0x4d, 0x28, 0xe4, 0xb8,
+    0xf8, 0x3d, 0x3b, 0x76, 0x77, 0x7a, 0x63, 0xb3, 0x27, 0xd7, 0x36, 0xbf, 0xfb, 0x01, 0x22, 0xed,
+    0x01, 0x90, 0x9c, 0xd7, 0x82, 0x7e, 0x02, 0x78, 0xe6, 0xb6, 0x08, 0x43, 0xa4, 0xab, 0xc7, 0xb1,
+    0x11, 0xd7, 0xf8, 0xb2, 0x72, 0x5c, 0xd5, 0x90, 0x2a, 0x6b, 0x20, 0xda, 0x7a, 0x29, 0x38, 0xfb,
+    0x19, 0x2b, 0xd3, 0x27, 0x44, 0x41, 0x67, 0x02, 0x27, 0xb4, 0xf6, 0x9a, 0x44, 0x00, 0x5b, 0x87,
+    0x11, 0x26, 0x6e, 0x47, 0x42, 0x27, 0xc6, 0x43, 0x9c, 0xa2, 0x5c, 0xa8, 0xe1, 0xec, 0x1f, 0xc2,
+    0x26, 0x09, 0x1e, 0x1c, 0xaf, 0xb0, 0xad, 0x8a, 0x4e, 0xa0, 0xa6, 0x94, 0xcd, 0x37, 0x43, 0xeb,
+    0xf5, 0x24, 0x77, 0x92, 0x33, 0xdb, 0x73, 0x4c, 0x45, 0x1d, 0x28, 0xb5, 0x8a, 0xa9, 0x75, 0x8e,
+    0x00, 0x9f, 0xf5, 0x0a, 0x6b, 0x8b, 0x11, 0xc3, 0xca, 0x6f, 0xdb, 0x26, 0x90, 0xa1, 0x24, 0xf8,
+    0xce, 0x25, 0x48, 0x9f, 0xef, 0xa6, 0x5a, 0x3e, 0x78, 0x2e, 0x7b, 0xa7, 0x0b, 0x66, 0x69, 0x0e,
+    0x06, 0x1c, 0x3f, 0xd0, 0xfd, 0x3d, 0xa2, 0x5d, 0x26, 0x07, 0xc2, 0x27, 0xd0, 0x90, 0xcc, 0xa7,
+    0x50, 0xed, 0x36, 0xc6, 0xec, 0x87, 0x87, 0x55, 0xe5, 0x37, 0xc1, 0xc4, 0x89, 0x51, 0xfb, 0x4c,
+    0x0f, 0xa1, 0x7a, 0xe9, 0xc2, 0x03, 0x33, 0x79, 0xdf, 0x7b, 0x5c, 0x65, 0xef, 0xf0, 0xe1, 0x07,
+    0x05, 0x5e, 0x9a, 0x27, 0x3e, 0x61, 0x19, 0xa2, 0x12, 0xdd, 0x09, 0xeb, 0x51, 0x70, 0x72, 0x19,
+    0x04, 0xea, 0xb2, 0x41, 0x38, 0x8a, 0x79, 0x81, 0x7f, 0xe0, 0xe0, 0xe2, 0xea, 0xd0, 0xb2, 0xec,
+    0x4f, 0xfd, 0xec, 0x51, 0xa1, 0x60, 0x28, 0xde, 0xe0, 0x20, 0x63, 0x4f, 0xd1, 0x29, 0xe7, 0x1c,
+    0x07, 0x23, 0x62, 0x56, 0xd2, 0x1c, 0x60, 0xd0, 0x2f, 0x0b, 0xdb, 0xf9, 0x5c, 0xff, 0x83, 0xe0,
+    0x3e, 0xa9, 0xe1, 0x6f, 0xca, 0x56, 0xb1, 0x8d, 0x55, 0x44, 0xb0, 0x88, 0x9a, 0x65, 0xc1, 0xf5,
+];
+
+/// [`GROTH16_PVK`] as a prepared key, built without validating the points.
+pub fn prepared_key(env: &Env) -> bn254::PreparedVerifyingKey {
+    let g1 = |i: usize| Bn254G1Affine::from_array(env, GROTH16_PVK[i..i + 64].try_into().unwrap());
+    let g2 = |i: usize| Bn254G2Affine::from_array(env, GROTH16_PVK[i..i + 128].try_into().unwrap());
+    bn254::PreparedVerifyingKey {
+        neg_alpha_g1: g1(0),
+        beta_g2: g2(64),
+        neg_gamma_g2: g2(192),
+        neg_delta_g2: g2(320),
+        gamma_abc_g1: vec![env, g1(448), g1(512), g1(576)],
+    }
+}
+
+/// Splits a proof blob into its selector and proof, validating the points
+/// like [`bn254::Proof::try_from_bytes`].
+pub fn decode_proof(bytes: Bytes) -> Result<(BytesN<4>, bn254::Proof), Groth16Error> {
+    if bytes.len() != PROOF_LEN {
+        return Err(Groth16Error::InvalidSp1ProofLength);
+    }
+    let selector = bytes
+        .slice(..4)
+        .try_into()
+        .map_err(|_| Groth16Error::InvalidSp1ProofLength)?;
+    Ok((selector, bn254::Proof::try_from_bytes(bytes.slice(4..))?))
+}
+
+/// sha256 of the public values with the top three bits cleared, so the
+/// digest is below the BN254 r.
+pub fn public_values_digest(env: &Env, public_values: &Bytes) -> BytesN<32> {
+    let mut digest = env.crypto().sha256(public_values).to_array();
+    digest[0] &= 0x1f;
+    BytesN::from_array(env, &digest)
+}
+
+/// The wrapper circuit's public inputs, `[program_vkey, digest]`.
+pub fn public_inputs(
+    env: &Env,
+    program_vkey: BytesN<32>,
+    public_values: &Bytes,
+) -> Vec<BytesN<32>> {
+    vec![env, program_vkey, public_values_digest(env, public_values)]
+}
+
+/// Verifies an SP1 proof blob that the program with vkey hash `program_vkey`
+/// committed `public_values`, under `pvk`, whose proofs carry `selector`.
+pub fn verify_proof(
+    env: &Env,
+    pvk: &bn254::PreparedVerifyingKey,
+    selector: &[u8; 4],
+    proof: Bytes,
+    program_vkey: BytesN<32>,
+    public_values: &Bytes,
+) -> Result<(), Groth16Error> {
+    let (proof_selector, proof) = decode_proof(proof)?;
+    if proof_selector.to_array() != *selector {
+        return Err(Groth16Error::Sp1VkSelectorMismatch);
+    }
+
+    let public_inputs = public_inputs(env, program_vkey, public_values);
+    let pi = groth16::to_field_elements::<Bn254>(env, public_inputs)?;
+    if !groth16::verify_prepared_proof(&env.crypto().bn254(), pvk, &proof, pi) {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+    Ok(())
+}
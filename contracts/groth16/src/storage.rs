@@ -0,0 +1,290 @@
+use soroban_sdk::{
+    contracttype,
+    crypto::bls12_381::{G1Affine, G2Affine},
+    Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
+};
+
+use crate::bls12_381::PreparedVerifyingKey;
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    PendingAdmin,
+    UpgradeDelay,
+    TtlConfig,
+    Vk(BytesN<32>),
+    Circuit(Symbol),
+    PendingVk(Symbol),
+    VkRefs(BytesN<32>),
+}
+
+// Keys are stored prepared, so the verification path never negates. Host
+// types are not contract types, so the points are stored with their raw
+// uncompressed encodings and decoded back into curve points on load.
+#[contracttype]
+#[derive(Clone)]
+pub struct StoredVk {
+    pub neg_alpha_g1: BytesN<96>,
+    pub beta_g2: BytesN<192>,
+    pub neg_gamma_g2: BytesN<192>,
+    pub neg_delta_g2: BytesN<192>,
+    pub gamma_abc_g1: Vec<BytesN<96>>,
+}
+
+impl StoredVk {
+    pub fn from_pvk(env: &Env, pvk: &PreparedVerifyingKey) -> Self {
+        let mut gamma_abc_g1 = Vec::new(env);
+        for p in pvk.gamma_abc_g1.iter() {
+            gamma_abc_g1.push_back(p.to_bytes());
+        }
+
+        StoredVk {
+            neg_alpha_g1: pvk.neg_alpha_g1.to_bytes(),
+            beta_g2: pvk.beta_g2.to_bytes(),
+            neg_gamma_g2: pvk.neg_gamma_g2.to_bytes(),
+            neg_delta_g2: pvk.neg_delta_g2.to_bytes(),
+            gamma_abc_g1,
+        }
+    }
+
+    pub fn into_pvk(self, env: &Env) -> PreparedVerifyingKey {
+        let mut gamma_abc_g1 = Vec::new(env);
+        for p in self.gamma_abc_g1.iter() {
+            gamma_abc_g1.push_back(G1Affine::from_bytes(p));
+        }
+
+        PreparedVerifyingKey {
+            neg_alpha_g1: G1Affine::from_bytes(self.neg_alpha_g1),
+            beta_g2: G2Affine::from_bytes(self.beta_g2),
+            neg_gamma_g2: G2Affine::from_bytes(self.neg_gamma_g2),
+            neg_delta_g2: G2Affine::from_bytes(self.neg_delta_g2),
+            gamma_abc_g1,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VkStatus {
+    Active,
+    /// Still verifies, but every use emits a warning event.
+    Deprecated,
+    Revoked,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkInfo {
+    pub version: u32,
+    pub status: VkStatus,
+    /// Ledger sequence from which a deprecated key is treated as revoked.
+    pub sunset_ledger: Option<u32>,
+}
+
+impl VkInfo {
+    /// Status as of the current ledger, with the sunset applied.
+    pub fn effective_status(&self, env: &Env) -> VkStatus {
+        match (self.status, self.sunset_ledger) {
+            (VkStatus::Deprecated, Some(sunset)) if env.ledger().sequence() >= sunset => {
+                VkStatus::Revoked
+            }
+            (status, _) => status,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct VkRecord {
+    pub info: VkInfo,
+    pub vk: StoredVk,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingVkInfo {
+    pub vk_id: BytesN<32>,
+    pub version: u32,
+    /// First ledger sequence at which the change can be executed.
+    pub eta_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingVkRecord {
+    pub info: PendingVkInfo,
+    pub vk: StoredVk,
+}
+
+/// TTL policy for persistent entries: written entries start with `extend_to`
+/// ledgers, and entries read on the verification path are extended back to
+/// `extend_to` once their TTL drops below `threshold`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+pub fn read_ttl_config(env: &Env) -> TtlConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::TtlConfig)
+        .unwrap_or(TtlConfig {
+            threshold: PERSISTENT_LIFETIME_THRESHOLD,
+            extend_to: PERSISTENT_BUMP_AMOUNT,
+        })
+}
+
+pub fn write_ttl_config(env: &Env, config: &TtlConfig) {
+    env.storage().instance().set(&DataKey::TtlConfig, config);
+}
+
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent(env: &Env, key: &DataKey) {
+    let config = read_ttl_config(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, config.threshold, config.extend_to);
+}
+
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let config = read_ttl_config(env);
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, config.extend_to, config.extend_to);
+}
+
+pub fn extend_vk(env: &Env, vk_id: &BytesN<32>) {
+    extend_persistent(env, &DataKey::Vk(vk_id.clone()));
+}
+
+pub fn extend_circuit(env: &Env, circuit: &Symbol) {
+    extend_persistent(env, &DataKey::Circuit(circuit.clone()));
+}
+
+pub fn has_vk(env: &Env, vk_id: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&DataKey::Vk(vk_id.clone()))
+}
+
+pub fn read_vk(env: &Env, vk_id: &BytesN<32>) -> Option<VkRecord> {
+    env.storage().persistent().get(&DataKey::Vk(vk_id.clone()))
+}
+
+pub fn write_vk(env: &Env, vk_id: &BytesN<32>, record: &VkRecord) {
+    set_persistent(env, &DataKey::Vk(vk_id.clone()), record);
+}
+
+pub fn remove_vk(env: &Env, vk_id: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Vk(vk_id.clone()));
+}
+
+// How many circuits and pending changes point at a key, so `remove_vk` does
+// not orphan them. The entry is dropped when the count returns to zero.
+pub fn read_vk_refs(env: &Env, vk_id: &BytesN<32>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VkRefs(vk_id.clone()))
+        .unwrap_or(0)
+}
+
+pub fn add_vk_ref(env: &Env, vk_id: &BytesN<32>) {
+    let refs = read_vk_refs(env, vk_id) + 1;
+    set_persistent(env, &DataKey::VkRefs(vk_id.clone()), &refs);
+}
+
+pub fn release_vk_ref(env: &Env, vk_id: &BytesN<32>) {
+    let key = DataKey::VkRefs(vk_id.clone());
+    match read_vk_refs(env, vk_id) {
+        0 | 1 => env.storage().persistent().remove(&key),
+        refs => set_persistent(env, &key, &(refs - 1)),
+    }
+}
+
+pub fn extend_vk_refs(env: &Env, vk_id: &BytesN<32>) {
+    let key = DataKey::VkRefs(vk_id.clone());
+    if env.storage().persistent().has(&key) {
+        extend_persistent(env, &key);
+    }
+}
+
+pub fn read_circuit(env: &Env, circuit: &Symbol) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Circuit(circuit.clone()))
+}
+
+pub fn write_circuit(env: &Env, circuit: &Symbol, vk_id: &BytesN<32>) {
+    set_persistent(env, &DataKey::Circuit(circuit.clone()), vk_id);
+}
+
+pub fn read_pending_vk(env: &Env, circuit: &Symbol) -> Option<PendingVkRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingVk(circuit.clone()))
+}
+
+/// A pending change is kept for `extend_to` ledgers past its `eta_ledger`,
+/// so it cannot be archived before it can be executed. The caller checks
+/// that the delay fits in the maximum TTL.
+pub fn write_pending_vk(env: &Env, circuit: &Symbol, record: &PendingVkRecord) {
+    let key = DataKey::PendingVk(circuit.clone());
+    let config = read_ttl_config(env);
+    let ttl = (record.info.eta_ledger - env.ledger().sequence())
+        .saturating_add(config.extend_to)
+        .min(env.storage().max_ttl());
+    env.storage().persistent().set(&key, record);
+    env.storage().persistent().extend_ttl(&key, ttl, ttl);
+}
+
+pub fn remove_pending_vk(env: &Env, circuit: &Symbol) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingVk(circuit.clone()));
+}
+
+pub fn read_upgrade_delay(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::UpgradeDelay)
+        .unwrap()
+}
+
+pub fn write_upgrade_delay(env: &Env, delay: u32) {
+    env.storage().instance().set(&DataKey::UpgradeDelay, &delay);
+}
+
+pub fn read_admin(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Admin).unwrap()
+}
+
+pub fn write_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+pub fn read_pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PendingAdmin)
+}
+
+pub fn write_pending_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::PendingAdmin, admin);
+}
+
+pub fn remove_pending_admin(env: &Env) {
+    env.storage().instance().remove(&DataKey::PendingAdmin);
+}
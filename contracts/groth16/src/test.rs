@@ -2,7 +2,13 @@
 extern crate std;
 
 use super::*;
-use soroban_sdk::{vec, BytesN, Env};
+use soroban_sdk::{
+    crypto::bls12_381::G1Affine,
+    events::Event as _,
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events, Ledger},
+    vec, Address, BytesN, Env,
+};
 
 pub const PROOF: [u8; 384] = [
     2, 125, 104, 204, 107, 146, 103, 71, 115, 229, 5, 186, 89, 248, 24, 222, 4, 148, 175, 174, 65,
@@ -105,7 +111,7 @@ fn test() {
         ),
     ];
 
-    let contract_id = env.register(Groth16Contract, ());
+    let contract_id = env.register(Groth16Contract, (Address::generate(&env), 0u32));
     let client = Groth16ContractClient::new(&env, &contract_id);
 
     let result = client.try_verify(&proof, &vk, &pi);
@@ -177,3 +183,1326 @@ fn test() {
 
     // assert_eq!(res, true);
 }
+
+pub const PUBLIC_INPUT: [u8; 32] = [
+    43, 208, 68, 170, 244, 217, 233, 104, 169, 196, 104, 2, 228, 225, 211, 30, 195, 13, 143, 171,
+    67, 82, 183, 9, 208, 189, 42, 151, 250, 111, 78, 199,
+];
+
+fn vk_bytes(env: &Env) -> Bytes {
+    Bytes::from_slice(env, [&VK[0..672], &P1, &P2].concat().as_slice())
+}
+
+fn public_inputs(env: &Env) -> Vec<BytesN<32>> {
+    vec![env, BytesN::from_array(env, &PUBLIC_INPUT)]
+}
+
+const UPGRADE_DELAY: u32 = 100;
+
+fn setup(env: &Env) -> (Groth16ContractClient<'_>, Address) {
+    let admin = Address::generate(env);
+    let contract_id = env.register(Groth16Contract, (admin.clone(), UPGRADE_DELAY));
+    (Groth16ContractClient::new(env, &contract_id), admin)
+}
+
+#[test]
+fn test_register_and_verify_with_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+
+    let proof = BytesN::from_array(&env, &PROOF);
+    assert!(client
+        .try_verify_with_id(&vk_id, &proof, &public_inputs(&env))
+        .is_ok());
+
+    let mut wrong_inputs = PUBLIC_INPUT;
+    wrong_inputs[31] ^= 1;
+    let wrong_inputs = vec![&env, BytesN::from_array(&env, &wrong_inputs)];
+    assert_eq!(
+        client.try_verify_with_id(&vk_id, &proof, &wrong_inputs),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+}
+
+/// Big-endian `a + b` over 256 bits, ignoring the final carry.
+fn add_be(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let sum = a[i] as u16 + b[i] as u16 + carry;
+        out[i] = sum as u8;
+        carry = sum >> 8;
+    }
+    out
+}
+
+#[test]
+fn test_non_canonical_public_input_is_rejected() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    let r: [u8; 32] = [
+        0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8,
+        0x05, 0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+        0x00, 0x01,
+    ];
+    let proof = BytesN::from_array(&env, &PROOF);
+
+    // The same field element, shifted by r, must not verify as an alias.
+    let aliased = vec![&env, BytesN::from_array(&env, &add_be(&PUBLIC_INPUT, &r))];
+    assert_eq!(
+        client.try_verify(&proof, &vk_bytes(&env), &aliased),
+        Err(Ok(Groth16Error::PublicInputGreaterThenFieldSize))
+    );
+
+    let modulus = vec![&env, BytesN::from_array(&env, &r)];
+    assert_eq!(
+        client.try_verify(&proof, &vk_bytes(&env), &modulus),
+        Err(Ok(Groth16Error::PublicInputGreaterThenFieldSize))
+    );
+
+    assert!(client
+        .try_verify(&proof, &vk_bytes(&env), &public_inputs(&env))
+        .is_ok());
+}
+
+#[test]
+fn test_verify_compressed() {
+    use ark_bls12_381::{G1Affine as ArkG1, G2Affine as ArkG2};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    let a = ArkG1::deserialize_uncompressed(&PROOF[0..96]).unwrap();
+    let b = ArkG2::deserialize_uncompressed(&PROOF[96..288]).unwrap();
+    let c = ArkG1::deserialize_uncompressed(&PROOF[288..384]).unwrap();
+    let mut compressed = [0u8; 192];
+    a.serialize_compressed(&mut compressed[0..48]).unwrap();
+    b.serialize_compressed(&mut compressed[48..144]).unwrap();
+    c.serialize_compressed(&mut compressed[144..192]).unwrap();
+
+    let proof = BytesN::from_array(&env, &compressed);
+    assert!(client
+        .try_verify_compressed(&proof, &vk_bytes(&env), &public_inputs(&env))
+        .is_ok());
+
+    // Flipping the sort flag of `a` selects -a.
+    compressed[0] ^= 0x20;
+    assert_eq!(
+        client.try_verify_compressed(
+            &BytesN::from_array(&env, &compressed),
+            &vk_bytes(&env),
+            &public_inputs(&env)
+        ),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    compressed[48] &= !0x80;
+    assert_eq!(
+        client.try_verify_compressed(
+            &BytesN::from_array(&env, &compressed),
+            &vk_bytes(&env),
+            &public_inputs(&env)
+        ),
+        Err(Ok(Groth16Error::DecompressingG2Failed))
+    );
+}
+
+/// The fixture key with every point re-encoded compressed.
+fn compressed_vk_bytes(env: &Env) -> Bytes {
+    use ark_bls12_381::{G1Affine as ArkG1, G2Affine as ArkG2};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let vk = vk_bytes(env).to_alloc_vec();
+    let mut out = std::vec::Vec::new();
+    ArkG1::deserialize_uncompressed(&vk[0..96])
+        .unwrap()
+        .serialize_compressed(&mut out)
+        .unwrap();
+    for g2 in vk[96..672].chunks(192) {
+        ArkG2::deserialize_uncompressed(g2)
+            .unwrap()
+            .serialize_compressed(&mut out)
+            .unwrap();
+    }
+    for g1 in vk[672..].chunks(96) {
+        ArkG1::deserialize_uncompressed(g1)
+            .unwrap()
+            .serialize_compressed(&mut out)
+            .unwrap();
+    }
+    Bytes::from_slice(env, &out)
+}
+
+#[test]
+fn test_compressed_vk_layout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk = compressed_vk_bytes(&env);
+    assert_eq!(vk.len(), 48 * (1 + 2) + 96 * 3);
+    let proof = BytesN::from_array(&env, &PROOF);
+    assert!(client.try_verify(&proof, &vk, &public_inputs(&env)).is_ok());
+
+    // Both layouts decode to the same key, and so to the same ID.
+    let vk_id = client.register_vk(&vk, &1, &1);
+    assert_eq!(client.register_vk(&vk_bytes(&env), &1, &1), vk_id);
+
+    assert_eq!(
+        client.try_verify(&proof, &vk.slice(..vk.len() - 1), &public_inputs(&env)),
+        Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+    );
+}
+
+#[test]
+fn test_verify_ark_serialized_vk() {
+    use ark_bls12_381::{Bls12_381, G1Affine as ArkG1, G2Affine as ArkG2};
+    use ark_groth16::VerifyingKey as ArkVerifyingKey;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let proof = BytesN::from_array(&env, &PROOF);
+
+    // `VK` is arkworks' uncompressed output, length prefix included.
+    let ark_vk = Bytes::from_slice(&env, &VK);
+    assert!(client
+        .try_verify_ark(&proof, &ark_vk, &public_inputs(&env))
+        .is_ok());
+
+    let vk = ArkVerifyingKey::<Bls12_381> {
+        alpha_g1: ArkG1::deserialize_uncompressed(&VK[0..96]).unwrap(),
+        beta_g2: ArkG2::deserialize_uncompressed(&VK[96..288]).unwrap(),
+        gamma_g2: ArkG2::deserialize_uncompressed(&VK[288..480]).unwrap(),
+        delta_g2: ArkG2::deserialize_uncompressed(&VK[480..672]).unwrap(),
+        gamma_abc_g1: std::vec![
+            ArkG1::deserialize_uncompressed(&P1[..]).unwrap(),
+            ArkG1::deserialize_uncompressed(&P2[..]).unwrap(),
+        ],
+    };
+    let mut compressed = std::vec::Vec::new();
+    vk.serialize_compressed(&mut compressed).unwrap();
+    assert!(client
+        .try_verify_ark(
+            &proof,
+            &Bytes::from_slice(&env, &compressed),
+            &public_inputs(&env)
+        )
+        .is_ok());
+
+    // A length prefix that disagrees with the input count.
+    let mut wrong_prefix = VK;
+    wrong_prefix[672] = 3;
+    assert_eq!(
+        client.try_verify_ark(
+            &proof,
+            &Bytes::from_slice(&env, &wrong_prefix),
+            &public_inputs(&env)
+        ),
+        Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+    );
+
+    // Counts whose layout length overflows a u32.
+    for n_inputs in [u32::MAX / 96, u32::MAX] {
+        assert!(matches!(
+            VerifyingKey::try_from_ark_bytes(Bytes::from_slice(&env, &VK), n_inputs),
+            Err(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs)
+        ));
+    }
+}
+
+#[test]
+fn test_register_vk_rejects_wrong_input_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    assert_eq!(
+        client.try_register_vk(&vk_bytes(&env), &2, &1),
+        Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+    );
+
+    // Counts whose layout length overflows a u32.
+    for n_inputs in [u32::MAX / 96, u32::MAX - 1, u32::MAX] {
+        assert_eq!(
+            client.try_register_vk(&vk_bytes(&env), &n_inputs, &1),
+            Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+        );
+    }
+    let circuit = symbol_short!("multiply");
+    client.set_circuit(&circuit, &client.register_vk(&vk_bytes(&env), &1, &1));
+    assert_eq!(
+        client.try_propose_circuit_vk(&circuit, &other_vk_bytes(&env), &u32::MAX, &2),
+        Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+    );
+}
+
+#[test]
+fn test_verify_with_unknown_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let proof = BytesN::from_array(&env, &PROOF);
+    assert_eq!(
+        client.try_verify_with_id(
+            &BytesN::from_array(&env, &[0; 32]),
+            &proof,
+            &public_inputs(&env)
+        ),
+        Err(Ok(Groth16Error::VerifyingKeyNotFound))
+    );
+}
+
+#[test]
+fn test_vk_id_is_content_addressed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk = vk_bytes(&env);
+    let vk_id = client.register_vk(&vk, &1, &1);
+
+    // The ID can be computed off-chain from the key bytes alone.
+    assert_eq!(vk_id, env.crypto().sha256(&vk).to_bytes());
+    assert_eq!(
+        vk_id,
+        groth16::vk_hash(&env, &VerifyingKey::try_from_bytes(vk.clone(), 1).unwrap())
+    );
+
+    // Registering the same key again is a no-op.
+    assert_eq!(client.register_vk(&vk, &1, &1), vk_id);
+
+    // BN254 keys get their IDs the same way.
+    let (bn254_vk, _) = groth16_test::fixtures::generate_bn254(&env, 1, 1, 5);
+    assert_eq!(
+        groth16::vk_hash(
+            &env,
+            &bn254::VerifyingKey::try_from_bytes(bn254_vk.clone(), 1).unwrap()
+        ),
+        env.crypto().sha256(&bn254_vk).to_bytes()
+    );
+}
+
+#[test]
+fn test_register_vk_requires_admin() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+
+    assert!(client.try_register_vk(&vk_bytes(&env), &1, &1).is_err());
+
+    env.mock_all_auths();
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    assert_eq!(env.auths()[0].0, admin);
+
+    client.remove_vk(&vk_id);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(
+        client.try_remove_vk(&vk_id),
+        Err(Ok(Groth16Error::VerifyingKeyNotFound))
+    );
+}
+
+#[test]
+fn test_circuit_binding() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let circuit = symbol_short!("multiply");
+    let proof = BytesN::from_array(&env, &PROOF);
+    assert_eq!(
+        client.try_verify_circuit(&circuit, &proof, &public_inputs(&env)),
+        Err(Ok(Groth16Error::CircuitNotFound))
+    );
+    assert_eq!(
+        client.try_set_circuit(&circuit, &BytesN::from_array(&env, &[0; 32])),
+        Err(Ok(Groth16Error::VerifyingKeyNotFound))
+    );
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+    assert_eq!(client.circuit_vk(&circuit), vk_id);
+    assert!(client
+        .try_verify_circuit(&circuit, &proof, &public_inputs(&env))
+        .is_ok());
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Groth16Error::NoPendingAdmin))
+    );
+
+    let new_admin = Address::generate(&env);
+    client.transfer_admin(&new_admin);
+    assert_eq!(env.auths()[0].0, admin);
+    assert_eq!(client.admin(), admin);
+
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.admin(), new_admin);
+    assert_eq!(
+        client.try_accept_admin(),
+        Err(Ok(Groth16Error::NoPendingAdmin))
+    );
+}
+
+#[test]
+fn test_deprecated_vk_warns_then_sunsets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &3);
+    let proof = BytesN::from_array(&env, &PROOF);
+    env.ledger().set_sequence_number(100);
+    client.deprecate_vk(&vk_id, &Some(200));
+    assert_eq!(
+        client.vk_info(&vk_id),
+        VkInfo {
+            version: 3,
+            status: VkStatus::Deprecated,
+            sunset_ledger: Some(200),
+        }
+    );
+
+    client.verify_with_id(&vk_id, &proof, &public_inputs(&env));
+    assert_eq!(
+        env.events().all(),
+        [DeprecatedVkUsed {
+            vk_id: vk_id.clone(),
+            version: 3,
+            sunset_ledger: Some(200),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    env.ledger().set_sequence_number(200);
+    assert_eq!(client.vk_info(&vk_id).status, VkStatus::Revoked);
+    assert_eq!(
+        client.try_verify_with_id(&vk_id, &proof, &public_inputs(&env)),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+    assert_eq!(
+        client.try_deprecate_vk(&vk_id, &None),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+}
+
+#[test]
+fn test_revoked_vk_fails_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.revoke_vk(&vk_id);
+    assert_eq!(client.vk_info(&vk_id).status, VkStatus::Revoked);
+
+    let proof = BytesN::from_array(&env, &PROOF);
+    assert_eq!(
+        client.try_verify_with_id(&vk_id, &proof, &public_inputs(&env)),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+}
+
+// A well-formed key that differs from the fixture key, for replacements.
+fn other_vk_bytes(env: &Env) -> Bytes {
+    Bytes::from_slice(env, [&VK[0..672], &P2, &P1].concat().as_slice())
+}
+
+#[test]
+fn test_timelocked_circuit_vk_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let circuit = symbol_short!("multiply");
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+    assert_eq!(
+        client.try_set_circuit(&circuit, &vk_id),
+        Err(Ok(Groth16Error::CircuitAlreadyBound))
+    );
+
+    env.ledger().set_sequence_number(10);
+    let pending = client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+    assert_eq!(pending.eta_ledger, 10 + UPGRADE_DELAY);
+    assert_eq!(client.pending_circuit_vk(&circuit), pending);
+    assert_eq!(
+        client.try_propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2),
+        Err(Ok(Groth16Error::PendingChangeExists))
+    );
+
+    env.ledger().set_sequence_number(pending.eta_ledger - 1);
+    assert_eq!(
+        client.try_execute_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::TimelockNotElapsed))
+    );
+    assert_eq!(client.circuit_vk(&circuit), vk_id);
+
+    env.ledger().set_sequence_number(pending.eta_ledger);
+    assert_eq!(client.execute_circuit_vk(&circuit), pending.vk_id);
+    assert_eq!(
+        env.events().all(),
+        [CircuitVkExecuted {
+            circuit: circuit.clone(),
+            vk_id: pending.vk_id.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.circuit_vk(&circuit), pending.vk_id);
+    assert_eq!(client.vk_info(&pending.vk_id).version, 2);
+    assert_eq!(
+        client.try_pending_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::PendingChangeNotFound))
+    );
+}
+
+#[test]
+fn test_cancel_circuit_vk_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let circuit = symbol_short!("multiply");
+    assert_eq!(
+        client.try_propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2),
+        Err(Ok(Groth16Error::CircuitNotFound))
+    );
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+    client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+    client.cancel_circuit_vk(&circuit);
+    assert_eq!(
+        client.try_pending_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::PendingChangeNotFound))
+    );
+    assert_eq!(
+        client.try_cancel_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::PendingChangeNotFound))
+    );
+
+    env.ledger().set_sequence_number(UPGRADE_DELAY);
+    assert_eq!(
+        client.try_execute_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::PendingChangeNotFound))
+    );
+    assert_eq!(client.circuit_vk(&circuit), vk_id);
+}
+
+#[test]
+fn test_pending_circuit_vk_outlives_its_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let delay = 20_000;
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Groth16Contract, (admin, delay));
+    let client = Groth16ContractClient::new(&env, &contract_id);
+
+    let circuit = symbol_short!("multiply");
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+
+    // A delay longer than the TTL entries are written with.
+    client.set_ttl_config(&TtlConfig {
+        threshold: 5_000,
+        extend_to: 10_000,
+    });
+    let pending = client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+    let ttl = env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&storage::DataKey::PendingVk(circuit.clone()))
+    });
+    assert_eq!(ttl, delay + 10_000);
+
+    env.ledger().set_sequence_number(pending.eta_ledger);
+    assert_eq!(client.pending_circuit_vk(&circuit), pending);
+    assert_eq!(client.execute_circuit_vk(&circuit), pending.vk_id);
+}
+
+#[test]
+fn test_execute_circuit_vk_rejects_revoked_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let circuit = symbol_short!("multiply");
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+    let pending = client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+
+    // The proposed key is registered and revoked while the change waits.
+    client.register_vk(&other_vk_bytes(&env), &1, &2);
+    client.revoke_vk(&pending.vk_id);
+
+    env.ledger().set_sequence_number(pending.eta_ledger);
+    assert_eq!(
+        client.try_execute_circuit_vk(&circuit),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+    assert_eq!(client.circuit_vk(&circuit), vk_id);
+}
+
+#[test]
+fn test_remove_vk_rejects_referenced_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let circuit = symbol_short!("multiply");
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    let other_id = client.register_vk(&other_vk_bytes(&env), &1, &2);
+    client.set_circuit(&circuit, &vk_id);
+    assert_eq!(
+        client.try_remove_vk(&vk_id),
+        Err(Ok(Groth16Error::VerifyingKeyInUse))
+    );
+
+    // A pending change holds the proposed key until it is cancelled.
+    client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+    assert_eq!(
+        client.try_remove_vk(&other_id),
+        Err(Ok(Groth16Error::VerifyingKeyInUse))
+    );
+    client.cancel_circuit_vk(&circuit);
+    client.remove_vk(&other_id);
+
+    // Once executed, the circuit holds the new key and releases the old one.
+    let pending = client.propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2);
+    env.ledger().set_sequence_number(pending.eta_ledger);
+    client.execute_circuit_vk(&circuit);
+    assert_eq!(
+        client.try_remove_vk(&pending.vk_id),
+        Err(Ok(Groth16Error::VerifyingKeyInUse))
+    );
+    client.remove_vk(&vk_id);
+    assert_eq!(client.vk_info(&client.circuit_vk(&circuit)).version, 2);
+}
+
+#[test]
+fn test_set_circuit_rejects_revoked_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.revoke_vk(&vk_id);
+    assert_eq!(
+        client.try_set_circuit(&symbol_short!("multiply"), &vk_id),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+
+    // A deprecated key past its sunset counts as revoked.
+    let other_id = client.register_vk(&other_vk_bytes(&env), &1, &2);
+    client.deprecate_vk(&other_id, &Some(50));
+    client.set_circuit(&symbol_short!("early"), &other_id);
+    env.ledger().set_sequence_number(50);
+    assert_eq!(
+        client.try_set_circuit(&symbol_short!("late"), &other_id),
+        Err(Ok(Groth16Error::VerifyingKeyRevoked))
+    );
+}
+
+#[test]
+fn test_propose_circuit_vk_rejects_unusable_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Groth16Contract, (admin, u32::MAX));
+    let client = Groth16ContractClient::new(&env, &contract_id);
+
+    let circuit = symbol_short!("multiply");
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    client.set_circuit(&circuit, &vk_id);
+    assert_eq!(
+        client.try_propose_circuit_vk(&circuit, &other_vk_bytes(&env), &1, &2),
+        Err(Ok(Groth16Error::InvalidUpgradeDelay))
+    );
+}
+
+fn vk_ttl(env: &Env, client: &Groth16ContractClient, vk_id: &BytesN<32>) -> u32 {
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&storage::DataKey::Vk(vk_id.clone()))
+    })
+}
+
+#[test]
+fn test_verify_with_id_extends_vk_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    client.set_ttl_config(&TtlConfig {
+        threshold: 1_000,
+        extend_to: 5_000,
+    });
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    assert_eq!(vk_ttl(&env, &client, &vk_id), 5_000);
+
+    // Above the threshold nothing is extended.
+    env.ledger().set_sequence_number(3_000);
+    let proof = BytesN::from_array(&env, &PROOF);
+    client.verify_with_id(&vk_id, &proof, &public_inputs(&env));
+    assert_eq!(vk_ttl(&env, &client, &vk_id), 2_000);
+
+    env.ledger().set_sequence_number(4_500);
+    client.verify_with_id(&vk_id, &proof, &public_inputs(&env));
+    assert_eq!(vk_ttl(&env, &client, &vk_id), 5_000);
+}
+
+#[test]
+fn test_bump_vk_is_permissionless() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    client.set_ttl_config(&TtlConfig {
+        threshold: 1_000,
+        extend_to: 5_000,
+    });
+    let vk_id = client.register_vk(&vk_bytes(&env), &1, &1);
+    env.ledger().set_sequence_number(4_500);
+
+    env.set_auths(&[]);
+    client.bump_vk(&vk_id);
+    assert!(env.auths().is_empty());
+    assert_eq!(vk_ttl(&env, &client, &vk_id), 5_000);
+    assert_eq!(
+        client.try_bump_vk(&BytesN::from_array(&env, &[0; 32])),
+        Err(Ok(Groth16Error::VerifyingKeyNotFound))
+    );
+}
+
+#[test]
+fn test_set_ttl_config_validates_bounds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    assert_eq!(
+        client.try_set_ttl_config(&TtlConfig {
+            threshold: 5_000,
+            extend_to: 1_000,
+        }),
+        Err(Ok(Groth16Error::InvalidTtlConfig))
+    );
+    assert_eq!(
+        client.try_set_ttl_config(&TtlConfig {
+            threshold: 0,
+            extend_to: u32::MAX,
+        }),
+        Err(Ok(Groth16Error::InvalidTtlConfig))
+    );
+}
+
+#[test]
+fn test_verify_batch() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    let (vk, fixtures) = groth16_test::fixtures::generate(&env, 3, 5, 1);
+    let mut proofs = Vec::new(&env);
+    let mut inputs = Vec::new(&env);
+    for (proof, pi) in fixtures {
+        proofs.push_back(proof);
+        inputs.push_back(pi);
+    }
+    assert!(client.try_verify_batch(&vk, &proofs, &inputs).is_ok());
+
+    // One bad proof fails the whole batch.
+    let mut tampered = inputs.clone();
+    tampered.set(2, inputs.get_unchecked(1));
+    assert_eq!(
+        client.try_verify_batch(&vk, &proofs, &tampered),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    inputs.pop_back();
+    assert_eq!(
+        client.try_verify_batch(&vk, &proofs, &inputs),
+        Err(Ok(Groth16Error::InvalidPublicInputsLength))
+    );
+    assert_eq!(
+        client.try_verify_batch(&vk, &Vec::new(&env), &Vec::new(&env)),
+        Err(Ok(Groth16Error::EmptyBatch))
+    );
+}
+
+#[test]
+fn test_verify_batch_is_cheaper_than_individual_verifies() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    let (vk, fixtures) = groth16_test::fixtures::generate(&env, 1, 8, 2);
+    let mut proofs = Vec::new(&env);
+    let mut inputs = Vec::new(&env);
+    for (proof, pi) in fixtures {
+        proofs.push_back(proof);
+        inputs.push_back(pi);
+    }
+
+    env.cost_estimate().budget().reset_unlimited();
+    client.verify_batch(&vk, &proofs, &inputs);
+    let batch_cost = env.cost_estimate().budget().cpu_instruction_cost();
+
+    // The budget only tracks the last invocation, so sum the single calls.
+    let mut individual_cost = 0;
+    for (proof, pi) in proofs.iter().zip(inputs.iter()) {
+        client.verify(&proof, &vk, &pi);
+        individual_cost += env.cost_estimate().budget().cpu_instruction_cost();
+    }
+
+    std::println!("verify_batch: {batch_cost}, 8 x verify: {individual_cost}");
+    assert!(batch_cost < individual_cost);
+}
+
+#[test]
+fn test_verify_multi_across_circuits() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    let (vk_a, proofs_a) = groth16_test::fixtures::generate(&env, 1, 2, 3);
+    let (vk_b, proofs_b) = groth16_test::fixtures::generate(&env, 2, 1, 4);
+    let mut entries = vec![
+        &env,
+        (
+            vk_bytes(&env),
+            BytesN::from_array(&env, &PROOF),
+            public_inputs(&env),
+        ),
+    ];
+    for (proof, pi) in proofs_a {
+        entries.push_back((vk_a.clone(), proof, pi));
+    }
+    for (proof, pi) in proofs_b {
+        entries.push_back((vk_b.clone(), proof, pi));
+    }
+    assert!(client.try_verify_multi(&entries).is_ok());
+
+    // An invalid entry for any circuit fails the whole set.
+    let (vk, proof, _) = entries.get_unchecked(3);
+    let mut tampered = entries.clone();
+    tampered.set(3, (vk, proof, entries.get_unchecked(2).2));
+    assert_eq!(
+        client.try_verify_multi(&tampered),
+        Err(Ok(Groth16Error::IncompatibleVerifyingKeyWithNrPublicInputs))
+    );
+    let (vk, proof, _) = entries.get_unchecked(1);
+    let mut tampered = entries.clone();
+    tampered.set(1, (vk, proof, entries.get_unchecked(2).2));
+    assert_eq!(
+        client.try_verify_multi(&tampered),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    assert_eq!(
+        client.try_verify_multi(&Vec::new(&env)),
+        Err(Ok(Groth16Error::EmptyBatch))
+    );
+}
+
+#[test]
+fn test_verify_many_reports_each_entry() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    let vk = vk_bytes(&env);
+    let proof = BytesN::from_array(&env, &PROOF);
+    let mut wrong_inputs = PUBLIC_INPUT;
+    wrong_inputs[31] ^= 1;
+    let mut malformed = PROOF;
+    malformed[0] |= 0x80;
+    let mut out_of_range = [0xffu8; 32];
+    out_of_range[0] = 0x7f;
+
+    let entries = vec![
+        &env,
+        (vk.clone(), proof.clone(), public_inputs(&env)),
+        (
+            vk.clone(),
+            proof.clone(),
+            vec![&env, BytesN::from_array(&env, &wrong_inputs)],
+        ),
+        (vk.clone(), proof.clone(), Vec::new(&env)),
+        (
+            vk.clone(),
+            BytesN::from_array(&env, &malformed),
+            public_inputs(&env),
+        ),
+        (vk.slice(1..), proof.clone(), public_inputs(&env)),
+        (
+            vk.clone(),
+            proof.clone(),
+            vec![&env, BytesN::from_array(&env, &out_of_range)],
+        ),
+        (vk, proof, public_inputs(&env)),
+    ];
+    assert_eq!(
+        client.verify_many(&entries),
+        vec![
+            &env,
+            STATUS_OK,
+            Groth16Error::ProofVerificationFailed as u32,
+            STATUS_MALFORMED,
+            STATUS_MALFORMED,
+            STATUS_MALFORMED,
+            STATUS_MALFORMED,
+            STATUS_OK,
+        ]
+    );
+}
+
+#[test]
+fn test_verify_with_commitments() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+    let (vk, proof, inputs) = groth16_test::fixtures::generate_with_commitment(&env, 7);
+
+    assert!(client
+        .try_verify_with_commitments(&proof, &vk, &inputs)
+        .is_ok());
+
+    // The commitment hash binds the public input.
+    let mut wrong_input = inputs.get_unchecked(0).to_array();
+    wrong_input[31] ^= 1;
+    assert_eq!(
+        client.try_verify_with_commitments(
+            &proof,
+            &vk,
+            &vec![&env, BytesN::from_array(&env, &wrong_input)]
+        ),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    // A proof of knowledge of some other commitment.
+    let mut bad_pok = proof.slice(..484);
+    bad_pok.append(&proof.slice(388..484));
+    assert_eq!(
+        client.try_verify_with_commitments(&bad_pok, &vk, &inputs),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    // Index 0 is the constant-one wire, which cannot be committed.
+    let mut bad_index = vk.slice(..392);
+    bad_index.extend_from_array(&0u32.to_be_bytes());
+    bad_index.append(&vk.slice(396..));
+    assert_eq!(
+        client.try_verify_with_commitments(&proof, &bad_index, &inputs),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    // A proof without its commitment.
+    let mut stripped = proof.slice(..384);
+    stripped.extend_from_array(&0u32.to_be_bytes());
+    assert_eq!(
+        client.try_verify_with_commitments(&stripped, &vk, &inputs),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+
+    assert_eq!(
+        client.try_verify_with_commitments(&proof.slice(..500), &vk, &inputs),
+        Err(Ok(Groth16Error::MalformedCommitments))
+    );
+}
+
+/// Checks the commitment hashing and proof of knowledge against a proof
+/// gnark itself produced, which the arkworks fixture above cannot: it shares
+/// the contract's assumptions about gnark.
+#[test]
+#[ignore = "needs the commit_* fixtures written by fixtures/gnark-commitment"]
+fn test_verify_gnark_commitment_fixtures() {
+    use groth16_gnark::{Curve, GnarkProof, GnarkVerifyingKey, GnarkWitness};
+
+    let read = |name: &str| {
+        let path = std::format!("{}/../../{name}", env!("CARGO_MANIFEST_DIR"));
+        std::fs::read(&path).unwrap_or_else(|e| panic!("reading {path}: {e}"))
+    };
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    let proof = GnarkProof::decode(Curve::Bls12_381, &read("commit_proof")).unwrap();
+    let vk = GnarkVerifyingKey::decode(Curve::Bls12_381, &read("commit_vk")).unwrap();
+    let witness = GnarkWitness::decode(Curve::Bls12_381, &read("commit_witness")).unwrap();
+    assert_eq!(vk.commitment_keys.len(), 1);
+
+    let proof = Bytes::from_slice(&env, &proof.to_contract_bytes_with_commitments());
+    let vk = Bytes::from_slice(&env, &vk.to_contract_bytes_with_commitments().unwrap());
+    let mut inputs = Vec::new(&env);
+    for input in witness.public() {
+        inputs.push_back(BytesN::from_array(&env, input));
+    }
+    assert!(client
+        .try_verify_with_commitments(&proof, &vk, &inputs)
+        .is_ok());
+
+    let mut wrong_input = inputs.get_unchecked(0).to_array();
+    wrong_input[31] ^= 1;
+    inputs.set(0, BytesN::from_array(&env, &wrong_input));
+    assert_eq!(
+        client.try_verify_with_commitments(&proof, &vk, &inputs),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+}
+
+#[test]
+fn test_verify_bn254() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    for n_inputs in [1, 3] {
+        let (vk, proofs) = groth16_test::fixtures::generate_bn254(&env, n_inputs, 2, 11);
+        for (proof, inputs) in &proofs {
+            assert!(client.try_verify_bn254(proof, &vk, inputs).is_ok());
+        }
+        // Each proof is bound to its own inputs.
+        assert_eq!(
+            client.try_verify_bn254(&proofs[0].0, &vk, &proofs[1].1),
+            Err(Ok(Groth16Error::ProofVerificationFailed))
+        );
+    }
+}
+
+#[test]
+fn test_verify_bn254_gnark_fixtures() {
+    use groth16_gnark::{Curve, GnarkProof, GnarkVerifyingKey, GnarkWitness};
+
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    let proof = GnarkProof::decode(Curve::Bn254, include_bytes!("../../../inner_proof")).unwrap();
+    let vk = GnarkVerifyingKey::decode(Curve::Bn254, include_bytes!("../../../inner_vk")).unwrap();
+    let witness =
+        GnarkWitness::decode(Curve::Bn254, include_bytes!("../../../inner_witness")).unwrap();
+
+    let proof = BytesN::from_array(
+        &env,
+        &proof.to_contract_bytes().unwrap().try_into().unwrap(),
+    );
+    let vk = Bytes::from_slice(&env, &vk.to_contract_bytes().unwrap());
+    let mut inputs = Vec::new(&env);
+    for input in witness.public() {
+        inputs.push_back(BytesN::from_array(&env, input));
+    }
+    assert!(client.try_verify_bn254(&proof, &vk, &inputs).is_ok());
+}
+
+#[test]
+fn test_verify_bn254_rejects_malformed_inputs() {
+    use ark_bn254::{Fq, Fq2, G2Affine};
+    use groth16_test::fixtures::{bn254_g2_bytes, generate_bn254};
+
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+    let (vk, proofs) = generate_bn254(&env, 1, 1, 3);
+    let (proof, inputs) = &proofs[0];
+
+    // input + r
+    let r = [
+        0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58,
+        0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00,
+        0x00, 0x01,
+    ];
+    let shifted = add_be(&inputs.get_unchecked(0).to_array(), &r);
+    assert_eq!(
+        client.try_verify_bn254(proof, &vk, &vec![&env, BytesN::from_array(&env, &shifted)]),
+        Err(Ok(Groth16Error::PublicInputGreaterThenFieldSize))
+    );
+
+    let mut bytes = proof.to_array();
+    bytes[0] |= 0x80;
+    assert_eq!(
+        client.try_verify_bn254(&BytesN::from_array(&env, &bytes), &vk, inputs),
+        Err(Ok(Groth16Error::DecompressingG1Failed))
+    );
+
+    let mut bytes = proof.to_array();
+    bytes[63] ^= 1;
+    assert_eq!(
+        client.try_verify_bn254(&BytesN::from_array(&env, &bytes), &vk, inputs),
+        Err(Ok(Groth16Error::PointNotOnCurve))
+    );
+
+    let mut bytes = proof.to_array();
+    bytes[0..64].fill(0);
+    assert_eq!(
+        client.try_verify_bn254(&BytesN::from_array(&env, &bytes), &vk, inputs),
+        Err(Ok(Groth16Error::PointAtInfinity))
+    );
+
+    // A twist point outside the prime-order subgroup passes the encoding
+    // and curve checks, and would trap in the host pairing.
+    let mut x = 1u64;
+    let b = loop {
+        if let Some(p) =
+            G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(x), Fq::from(0)), false)
+        {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                break p;
+            }
+        }
+        x += 1;
+    };
+    let mut bytes = proof.to_array();
+    bytes[64..192].copy_from_slice(&bn254_g2_bytes(&b));
+    assert_eq!(
+        client.try_verify_bn254(&BytesN::from_array(&env, &bytes), &vk, inputs),
+        Err(Ok(Groth16Error::PointNotInSubgroup))
+    );
+}
+
+#[test]
+fn test_verify_sp1() {
+    use groth16_test::fixtures::generate_sp1;
+
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let (client, _) = setup(&env);
+
+    // Program vkey hashes are BN254 scalars.
+    let mut program_vkey = [0x5au8; 32];
+    program_vkey[0] = 0x00;
+    let public_values = b"sp1 public values";
+    let selector = [0x01, 0x02, 0x03, 0x04];
+    let (vk, proof) = generate_sp1(&env, &selector, &program_vkey, public_values, 7);
+    let vk = bn254::VerifyingKey::try_from_bytes(vk, 2).unwrap();
+    let pvk = bn254::PreparedVerifyingKey::from(&vk);
+    let program_vkey = BytesN::from_array(&env, &program_vkey);
+    let public_values = Bytes::from_slice(&env, public_values);
+    let verify = |proof: &Bytes, program_vkey: &BytesN<32>, public_values: &Bytes| {
+        sp1::verify_proof(
+            &env,
+            &pvk,
+            &selector,
+            proof.clone(),
+            program_vkey.clone(),
+            public_values,
+        )
+    };
+
+    assert_eq!(verify(&proof, &program_vkey, &public_values), Ok(()));
+
+    // Other public values or another program.
+    assert_eq!(
+        verify(
+            &proof,
+            &program_vkey,
+            &Bytes::from_slice(&env, b"other public values")
+        ),
+        Err(Groth16Error::ProofVerificationFailed)
+    );
+    let mut other = program_vkey.to_array();
+    other[31] ^= 1;
+    assert_eq!(
+        verify(&proof, &BytesN::from_array(&env, &other), &public_values),
+        Err(Groth16Error::ProofVerificationFailed)
+    );
+
+    // Another key's selector, and a truncated blob.
+    let mut unknown = proof.clone();
+    unknown.set(0, proof.get_unchecked(0) ^ 1);
+    assert_eq!(
+        verify(&unknown, &program_vkey, &public_values),
+        Err(Groth16Error::Sp1VkSelectorMismatch)
+    );
+    assert_eq!(
+        verify(&proof.slice(..259), &program_vkey, &public_values),
+        Err(Groth16Error::InvalidSp1ProofLength)
+    );
+
+    // The contract only takes proofs for the pinned SP1 key.
+    assert_eq!(
+        client.try_verify_sp1(&proof, &program_vkey, &public_values),
+        Err(Ok(Groth16Error::Sp1VkSelectorMismatch))
+    );
+    let mut pinned = Bytes::from_array(&env, &sp1::GROTH16_VK_SELECTOR);
+    pinned.append(&proof.slice(4..));
+    assert_eq!(
+        client.try_verify_sp1(&pinned, &program_vkey, &public_values),
+        Err(Ok(Groth16Error::ProofVerificationFailed))
+    );
+}
+
+/// The pinned key and selector are SP1 v5's `groth16_vk.bin`, a copy of
+/// which is kept in `fixtures/sp1`. The key is checked here, once, as
+/// `verify_bn254` would check it, and then prepared.
+#[test]
+fn test_sp1_pvk_matches_groth16_vk_bin() {
+    use groth16_gnark::{Curve, GnarkVerifyingKey};
+    use sha2::{Digest, Sha256};
+
+    const GROTH16_VK_BIN: &[u8] = include_bytes!("../../../fixtures/sp1/groth16_vk.bin");
+
+    assert_eq!(
+        Sha256::digest(GROTH16_VK_BIN)[..4],
+        sp1::GROTH16_VK_SELECTOR
+    );
+    let vk = GnarkVerifyingKey::decode(Curve::Bn254, GROTH16_VK_BIN).unwrap();
+    assert_eq!(vk.n_public(), 2);
+
+    let env = Env::default();
+    let vk = bn254::VerifyingKey::try_from_bytes(
+        Bytes::from_slice(&env, &vk.to_contract_bytes().unwrap()),
+        2,
+    )
+    .unwrap();
+    let expected = bn254::PreparedVerifyingKey::from(&vk);
+    let pvk = sp1::prepared_key(&env);
+    assert_eq!(
+        pvk.neg_alpha_g1.to_bytes(),
+        expected.neg_alpha_g1.to_bytes()
+    );
+    assert_eq!(pvk.beta_g2.to_bytes(), expected.beta_g2.to_bytes());
+    assert_eq!(
+        pvk.neg_gamma_g2.to_bytes(),
+        expected.neg_gamma_g2.to_bytes()
+    );
+    assert_eq!(
+        pvk.neg_delta_g2.to_bytes(),
+        expected.neg_delta_g2.to_bytes()
+    );
+    assert_eq!(pvk.gamma_abc_g1.len(), expected.gamma_abc_g1.len());
+    for (p, q) in pvk.gamma_abc_g1.iter().zip(expected.gamma_abc_g1.iter()) {
+        assert_eq!(p.to_bytes(), q.to_bytes());
+    }
+}
+
+/// Checks `sp1::verify_proof` against a proof SP1 itself produced: the
+/// Fibonacci example proven with SP1 v3.0.0, under that release's key. The
+/// proof is its `bytes()`, `vkey` the program's `vk.bytes32_raw()`.
+#[test]
+fn test_verify_sp1_v3_fixture() {
+    use groth16_gnark::{Curve, GnarkVerifyingKey};
+    use sha2::{Digest, Sha256};
+
+    const GROTH16_VK_BIN: &[u8] = include_bytes!("../../../fixtures/sp1/v3.0.0/groth16_vk.bin");
+    const PROOF: &[u8] = include_bytes!("../../../fixtures/sp1/v3.0.0/proof");
+    const PUBLIC_VALUES: &[u8] = include_bytes!("../../../fixtures/sp1/v3.0.0/public_values");
+    const VKEY: &[u8; 32] = include_bytes!("../../../fixtures/sp1/v3.0.0/vkey");
+
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let selector: [u8; 4] = Sha256::digest(GROTH16_VK_BIN)[..4].try_into().unwrap();
+    let vk = GnarkVerifyingKey::decode(Curve::Bn254, GROTH16_VK_BIN).unwrap();
+    let vk = bn254::VerifyingKey::try_from_bytes(
+        Bytes::from_slice(&env, &vk.to_contract_bytes().unwrap()),
+        2,
+    )
+    .unwrap();
+    let pvk = bn254::PreparedVerifyingKey::from(&vk);
+    let proof = Bytes::from_slice(&env, PROOF);
+    let program_vkey = BytesN::from_array(&env, VKEY);
+    let public_values = Bytes::from_slice(&env, PUBLIC_VALUES);
+
+    assert_eq!(
+        sp1::verify_proof(
+            &env,
+            &pvk,
+            &selector,
+            proof.clone(),
+            program_vkey.clone(),
+            &public_values
+        ),
+        Ok(())
+    );
+
+    let mut other = public_values.clone();
+    other.push_back(0);
+    assert_eq!(
+        sp1::verify_proof(&env, &pvk, &selector, proof.clone(), program_vkey, &other),
+        Err(Groth16Error::ProofVerificationFailed)
+    );
+
+    // The v3.0.0 key is not the pinned one.
+    let (client, _) = setup(&env);
+    assert_eq!(
+        client.try_verify_sp1(&proof, &BytesN::from_array(&env, VKEY), &public_values),
+        Err(Ok(Groth16Error::Sp1VkSelectorMismatch))
+    );
+}
+
+/// `n` public inputs and the `n + 1` key points they are accumulated over.
+fn accumulation_inputs(env: &Env, n: u32) -> (Vec<G1Affine>, Vec<Fr>) {
+    let bls = env.crypto().bls12_381();
+    let dst = Bytes::from_slice(env, b"GROTH16-BENCH");
+    let mut points = Vec::new(env);
+    let mut inputs = Vec::new(env);
+    for i in 0..=n {
+        points.push_back(bls.hash_to_g1(&Bytes::from_array(env, &i.to_be_bytes()), &dst));
+        if i > 0 {
+            let x = u128::MAX / (i as u128 + 1);
+            inputs.push_back(Fr::from_u256(U256::from_u128(env, x)));
+        }
+    }
+    (points, inputs)
+}
+
+/// One g1_mul and one g1_add host call per input.
+fn accumulate_with_loop(env: &Env, points: &Vec<G1Affine>, inputs: &Vec<Fr>) -> G1Affine {
+    let bls = env.crypto().bls12_381();
+    let mut acc = points.get_unchecked(0);
+    for (i, input) in inputs.iter().enumerate() {
+        let term = bls.g1_mul(&points.get_unchecked(i as u32 + 1), &input);
+        acc = bls.g1_add(&acc, &term);
+    }
+    acc
+}
+
+#[test]
+fn test_accumulate_inputs_matches_loop() {
+    let env = Env::default();
+    env.cost_estimate().budget().reset_unlimited();
+    let bls = env.crypto().bls12_381();
+
+    for n in [0u32, 1, 8] {
+        let (points, inputs) = accumulation_inputs(&env, n);
+        let expected = accumulate_with_loop(&env, &points, &inputs);
+        assert_eq!(groth16::accumulate_inputs(&bls, &points, inputs), expected);
+    }
+}
+
+/// Compares the CPU cost of the host MSM with the per-input loop.
+#[test]
+#[ignore = "benchmark, run with --ignored --nocapture"]
+fn bench_input_accumulation() {
+    let env = Env::default();
+    let bls = env.crypto().bls12_381();
+
+    for n in [1u32, 8, 32, 64] {
+        let (points, inputs) = accumulation_inputs(&env, n);
+
+        env.cost_estimate().budget().reset_unlimited();
+        accumulate_with_loop(&env, &points, &inputs);
+        let loop_cost = env.cost_estimate().budget().cpu_instruction_cost();
+
+        env.cost_estimate().budget().reset_unlimited();
+        groth16::accumulate_inputs(&bls, &points, inputs);
+        let msm_cost = env.cost_estimate().budget().cpu_instruction_cost();
+
+        std::println!(
+            "n = {n:>2}: g1_mul + g1_add loop {loop_cost:>11} cpu, g1_msm {msm_cost:>11} cpu"
+        );
+    }
+}
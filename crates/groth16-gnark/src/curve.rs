@@ -0,0 +1,154 @@
+//! Point and scalar decoding for each curve gnark supports here. Points are
+//! returned in the uncompressed big-endian encoding the contract takes for
+//! that curve.
+
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{reader::Reader, Error};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Bn254,
+    Bls12_381,
+}
+
+/// Parses a canonical big-endian field element.
+fn fp_from_be<F: PrimeField>(bytes: &[u8]) -> Result<F, Error> {
+    let f = F::from_be_bytes_mod_order(bytes);
+    if f.into_bigint().to_bytes_be() != bytes {
+        return Err(Error::OutOfRange);
+    }
+    Ok(f)
+}
+
+fn fp_to_be<F: PrimeField>(f: &F) -> Vec<u8> {
+    f.into_bigint().to_bytes_be()
+}
+
+// gnark's BN254 flags, in the top two bits of the first byte.
+const BN254_MASK: u8 = 0b11 << 6;
+const BN254_UNCOMPRESSED: u8 = 0b00 << 6;
+const BN254_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+const BN254_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+/// Ethereum-style BN254 encoding, with the point at infinity all zeros.
+fn bn254_g1_bytes(p: &ark_bn254::G1Affine) -> Vec<u8> {
+    match p.xy() {
+        None => vec![0; 64],
+        Some((x, y)) => [fp_to_be(x), fp_to_be(y)].concat(),
+    }
+}
+
+fn bn254_g2_bytes(p: &ark_bn254::G2Affine) -> Vec<u8> {
+    match p.xy() {
+        None => vec![0; 128],
+        Some((x, y)) => [
+            fp_to_be(&x.c1),
+            fp_to_be(&x.c0),
+            fp_to_be(&y.c1),
+            fp_to_be(&y.c0),
+        ]
+        .concat(),
+    }
+}
+
+fn bn254_fq2(bytes: &[u8]) -> Result<ark_bn254::Fq2, Error> {
+    Ok(ark_bn254::Fq2::new(
+        fp_from_be(&bytes[32..64])?,
+        fp_from_be(&bytes[0..32])?,
+    ))
+}
+
+fn checked<P: SWCurveConfig>(p: Affine<P>) -> Option<Affine<P>> {
+    (p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve()).then_some(p)
+}
+
+/// Reads one BN254 point of `size` uncompressed bytes in gnark's encoding:
+/// either uncompressed, or the x coordinate with the flags recording which
+/// y to pick.
+fn bn254_read<P: SWCurveConfig>(
+    r: &mut Reader,
+    size: usize,
+    parse_base: impl Fn(&[u8]) -> Result<P::BaseField, Error>,
+    invalid: Error,
+) -> Result<Affine<P>, Error> {
+    let flags = r.peek()? & BN254_MASK;
+    if flags == BN254_UNCOMPRESSED {
+        let bytes = r.take(size)?;
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Affine::identity());
+        }
+        let (x, y) = bytes.split_at(size / 2);
+        let p = Affine::new_unchecked(parse_base(x)?, parse_base(y)?);
+        return checked(p).ok_or(invalid);
+    }
+
+    let mut x = r.take(size / 2)?.to_vec();
+    x[0] &= !BN254_MASK;
+    if flags == BN254_COMPRESSED_INFINITY {
+        return if x.iter().all(|b| *b == 0) {
+            Ok(Affine::identity())
+        } else {
+            Err(invalid)
+        };
+    }
+    let largest = flags == BN254_COMPRESSED_LARGEST;
+    Affine::get_point_from_x_unchecked(parse_base(&x)?, largest)
+        .and_then(checked)
+        .ok_or(invalid)
+}
+
+/// Reads one BLS12-381 point. gnark uses the ZCash flags, so arkworks
+/// decodes it directly; the compression flag tells the two sizes apart.
+fn bls12_381_read<T: CanonicalDeserialize + CanonicalSerialize>(
+    r: &mut Reader,
+    compressed_size: usize,
+    invalid: Error,
+) -> Result<Vec<u8>, Error> {
+    let p = if r.peek()? & 0x80 != 0 {
+        T::deserialize_compressed(r.take(compressed_size)?)
+    } else {
+        T::deserialize_uncompressed(r.take(2 * compressed_size)?)
+    }
+    .map_err(|_| invalid)?;
+    let mut out = Vec::with_capacity(2 * compressed_size);
+    p.serialize_uncompressed(&mut out).unwrap();
+    Ok(out)
+}
+
+impl Curve {
+    pub(crate) fn read_g1(self, r: &mut Reader) -> Result<Vec<u8>, Error> {
+        match self {
+            Curve::Bn254 => {
+                let p = bn254_read::<ark_bn254::g1::Config>(r, 64, fp_from_be, Error::InvalidG1)?;
+                Ok(bn254_g1_bytes(&p))
+            }
+            Curve::Bls12_381 => bls12_381_read::<ark_bls12_381::G1Affine>(r, 48, Error::InvalidG1),
+        }
+    }
+
+    pub(crate) fn read_g2(self, r: &mut Reader) -> Result<Vec<u8>, Error> {
+        match self {
+            Curve::Bn254 => {
+                let p = bn254_read::<ark_bn254::g2::Config>(r, 128, bn254_fq2, Error::InvalidG2)?;
+                Ok(bn254_g2_bytes(&p))
+            }
+            Curve::Bls12_381 => bls12_381_read::<ark_bls12_381::G2Affine>(r, 96, Error::InvalidG2),
+        }
+    }
+
+    /// Reads a 32-byte big-endian scalar, which must be below r.
+    pub(crate) fn read_fr(self, r: &mut Reader) -> Result<[u8; 32], Error> {
+        let bytes = r.take(32)?;
+        match self {
+            Curve::Bn254 => fp_from_be::<ark_bn254::Fr>(bytes).map(|_| ())?,
+            Curve::Bls12_381 => fp_from_be::<ark_bls12_381::Fr>(bytes).map(|_| ())?,
+        }
+        Ok(bytes.try_into().unwrap())
+    }
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+    #[error("{0} trailing bytes after the encoding")]
+    TrailingBytes(usize),
+    #[error("invalid G1 point encoding")]
+    InvalidG1,
+    #[error("invalid G2 point encoding")]
+    InvalidG2,
+    #[error("field element is not below the modulus")]
+    OutOfRange,
+    #[error("witness declares {declared} values but holds {actual}")]
+    WitnessLengthMismatch { declared: usize, actual: usize },
+    #[error("Pedersen commitments need the `verify_with_commitments` layouts")]
+    UnsupportedCommitments,
+    #[error("K holds {k} points, fewer than the {needed} its commitments need")]
+    TooFewKeyPoints { k: usize, needed: usize },
+    #[error("{keys} commitment keys for {committed} committed input lists")]
+    CommitmentKeysMismatch { keys: usize, committed: usize },
+}
@@ -0,0 +1,244 @@
+//! Decodes gnark's binary Groth16 encodings (`Proof.WriteTo`,
+//! `VerifyingKey.WriteTo` and `witness.MarshalBinary`) into the byte
+//! layouts the contract takes.
+//!
+//! Points may be compressed or not, as gnark's decoder allows. Each is
+//! decompressed and checked to be on the curve and in the prime-order
+//! subgroup, then stored uncompressed: the ZCash encoding for BLS12-381 and
+//! the Ethereum encoding for BN254.
+
+mod curve;
+mod error;
+mod reader;
+
+pub use curve::Curve;
+pub use error::Error;
+
+use reader::Reader;
+
+fn read_g1_slice(curve: Curve, r: &mut Reader) -> Result<Vec<Vec<u8>>, Error> {
+    (0..r.u32()?).map(|_| curve.read_g1(r)).collect()
+}
+
+/// gnark `groth16.Proof`: `Ar || Bs || Krs || Commitments || CommitmentPok`,
+/// where `Commitments` is a `u32` count followed by G1 points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkProof {
+    pub curve: Curve,
+    pub ar: Vec<u8>,
+    pub bs: Vec<u8>,
+    pub krs: Vec<u8>,
+    pub commitments: Vec<Vec<u8>>,
+    pub commitment_pok: Vec<u8>,
+}
+
+impl GnarkProof {
+    pub fn decode(curve: Curve, bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes);
+        let proof = GnarkProof {
+            curve,
+            ar: curve.read_g1(&mut r)?,
+            bs: curve.read_g2(&mut r)?,
+            krs: curve.read_g1(&mut r)?,
+            commitments: read_g1_slice(curve, &mut r)?,
+            commitment_pok: curve.read_g1(&mut r)?,
+        };
+        r.finish()?;
+        Ok(proof)
+    }
+
+    /// The contract's `a || b || c` proof layout.
+    pub fn to_contract_bytes(&self) -> Result<Vec<u8>, Error> {
+        if !self.commitments.is_empty() {
+            return Err(Error::UnsupportedCommitments);
+        }
+        Ok([&self.ar[..], &self.bs, &self.krs].concat())
+    }
+
+    /// The contract's `verify_with_commitments` proof layout: `a || b || c`,
+    /// a big-endian `u32` commitment count, the commitments and, if there
+    /// are any, the proof of knowledge.
+    pub fn to_contract_bytes_with_commitments(&self) -> Vec<u8> {
+        let mut out = [&self.ar[..], &self.bs, &self.krs].concat();
+        out.extend_from_slice(&(self.commitments.len() as u32).to_be_bytes());
+        for c in &self.commitments {
+            out.extend_from_slice(c);
+        }
+        if !self.commitments.is_empty() {
+            out.extend_from_slice(&self.commitment_pok);
+        }
+        out
+    }
+}
+
+/// gnark `pedersen.VerifyingKey`: `G || GSigmaNeg`, both in G2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkCommitmentKey {
+    pub g: Vec<u8>,
+    pub g_sigma_neg: Vec<u8>,
+}
+
+/// gnark `groth16.VerifyingKey`: `[alpha]1 || [beta]1 || [beta]2 ||
+/// [gamma]2 || [delta]1 || [delta]2 || K || PublicAndCommitmentCommitted ||
+/// CommitmentKeys`, where `K` is a `u32` count followed by G1 points,
+/// `PublicAndCommitmentCommitted` is a `u32` count of `u32`-counted `u64`
+/// lists, and `CommitmentKeys` is a `u32` count of Pedersen keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkVerifyingKey {
+    pub curve: Curve,
+    pub alpha_g1: Vec<u8>,
+    pub beta_g1: Vec<u8>,
+    pub beta_g2: Vec<u8>,
+    pub gamma_g2: Vec<u8>,
+    pub delta_g1: Vec<u8>,
+    pub delta_g2: Vec<u8>,
+    /// `gamma_abc_g1` in arkworks terms: one point per public input, plus one.
+    pub k: Vec<Vec<u8>>,
+    pub public_and_commitment_committed: Vec<Vec<u64>>,
+    pub commitment_keys: Vec<GnarkCommitmentKey>,
+}
+
+impl GnarkVerifyingKey {
+    pub fn decode(curve: Curve, bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes);
+        let alpha_g1 = curve.read_g1(&mut r)?;
+        let beta_g1 = curve.read_g1(&mut r)?;
+        let beta_g2 = curve.read_g2(&mut r)?;
+        let gamma_g2 = curve.read_g2(&mut r)?;
+        let delta_g1 = curve.read_g1(&mut r)?;
+        let delta_g2 = curve.read_g2(&mut r)?;
+        let k = read_g1_slice(curve, &mut r)?;
+        let public_and_commitment_committed: Vec<Vec<u64>> = (0..r.u32()?)
+            .map(|_| (0..r.u32()?).map(|_| r.u64()).collect())
+            .collect::<Result<_, _>>()?;
+        let commitment_keys = (0..r.u32()?)
+            .map(|_| {
+                Ok(GnarkCommitmentKey {
+                    g: curve.read_g2(&mut r)?,
+                    g_sigma_neg: curve.read_g2(&mut r)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        r.finish()?;
+
+        // K has the constant-one wire's point and one per commitment hash.
+        let needed = 1 + public_and_commitment_committed.len();
+        if k.len() < needed {
+            return Err(Error::TooFewKeyPoints { k: k.len(), needed });
+        }
+
+        Ok(GnarkVerifyingKey {
+            curve,
+            alpha_g1,
+            beta_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g1,
+            delta_g2,
+            k,
+            public_and_commitment_committed,
+            commitment_keys,
+        })
+    }
+
+    /// Number of public inputs the key verifies, not counting gnark's
+    /// leading constant-one wire or the commitment hashes. [`Self::decode`]
+    /// rejects keys too short for this to underflow.
+    pub fn n_public(&self) -> usize {
+        self.k.len() - 1 - self.public_and_commitment_committed.len()
+    }
+
+    /// The contract's `alpha || beta || gamma || delta || gamma_abc_g1`
+    /// key layout.
+    pub fn to_contract_bytes(&self) -> Result<Vec<u8>, Error> {
+        if !self.commitment_keys.is_empty() {
+            return Err(Error::UnsupportedCommitments);
+        }
+        Ok(self.key_bytes())
+    }
+
+    /// The contract's `verify_with_commitments` key layout: a big-endian
+    /// `u32` commitment count; per commitment, `g || g_sigma_neg` and the
+    /// `u32`-counted committed indices as `u32`s; then the
+    /// [`Self::to_contract_bytes`] layout, with `K` holding a point per
+    /// commitment hash after the public inputs'.
+    pub fn to_contract_bytes_with_commitments(&self) -> Result<Vec<u8>, Error> {
+        if self.commitment_keys.len() != self.public_and_commitment_committed.len() {
+            return Err(Error::CommitmentKeysMismatch {
+                keys: self.commitment_keys.len(),
+                committed: self.public_and_commitment_committed.len(),
+            });
+        }
+        let mut out = (self.commitment_keys.len() as u32).to_be_bytes().to_vec();
+        for (key, committed) in self
+            .commitment_keys
+            .iter()
+            .zip(&self.public_and_commitment_committed)
+        {
+            out.extend_from_slice(&key.g);
+            out.extend_from_slice(&key.g_sigma_neg);
+            out.extend_from_slice(&(committed.len() as u32).to_be_bytes());
+            for &index in committed {
+                let index = u32::try_from(index).map_err(|_| Error::OutOfRange)?;
+                out.extend_from_slice(&index.to_be_bytes());
+            }
+        }
+        out.extend_from_slice(&self.key_bytes());
+        Ok(out)
+    }
+
+    fn key_bytes(&self) -> Vec<u8> {
+        let mut out = [
+            &self.alpha_g1[..],
+            &self.beta_g2,
+            &self.gamma_g2,
+            &self.delta_g2,
+        ]
+        .concat();
+        for p in &self.k {
+            out.extend_from_slice(p);
+        }
+        out
+    }
+}
+
+/// gnark `witness.Witness` binary encoding: `u32` public count, `u32`
+/// secret count, then a `u32`-counted vector of 32-byte big-endian scalars,
+/// public values first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkWitness {
+    pub n_public: u32,
+    pub n_secret: u32,
+    pub values: Vec<[u8; 32]>,
+}
+
+impl GnarkWitness {
+    pub fn decode(curve: Curve, bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes);
+        let n_public = r.u32()?;
+        let n_secret = r.u32()?;
+        let len = r.u32()? as usize;
+        let declared = n_public as usize + n_secret as usize;
+        if len != declared {
+            return Err(Error::WitnessLengthMismatch {
+                declared,
+                actual: len,
+            });
+        }
+        let values = (0..len)
+            .map(|_| curve.read_fr(&mut r))
+            .collect::<Result<_, _>>()?;
+        r.finish()?;
+
+        Ok(GnarkWitness {
+            n_public,
+            n_secret,
+            values,
+        })
+    }
+
+    /// The public values, which are the contract's `public_inputs`.
+    pub fn public(&self) -> &[[u8; 32]] {
+        &self.values[..self.n_public as usize]
+    }
+}
@@ -0,0 +1,164 @@
+//! Decodes the gnark BN254 fixtures at the repository root and checks that
+//! they form a valid proof, key and public witness.
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use groth16_gnark::{Curve, Error, GnarkProof, GnarkVerifyingKey, GnarkWitness};
+
+const INNER_PROOF: &[u8] = include_bytes!("../../../inner_proof");
+const INNER_VK: &[u8] = include_bytes!("../../../inner_vk");
+const INNER_WITNESS: &[u8] = include_bytes!("../../../inner_witness");
+
+fn g1(bytes: &[u8]) -> G1Affine {
+    if bytes.iter().all(|b| *b == 0) {
+        return G1Affine::zero();
+    }
+    G1Affine::new(
+        Fq::from_be_bytes_mod_order(&bytes[0..32]),
+        Fq::from_be_bytes_mod_order(&bytes[32..64]),
+    )
+}
+
+fn g2(bytes: &[u8]) -> G2Affine {
+    let fq2 = |b: &[u8]| {
+        Fq2::new(
+            Fq::from_be_bytes_mod_order(&b[32..64]),
+            Fq::from_be_bytes_mod_order(&b[0..32]),
+        )
+    };
+    G2Affine::new(fq2(&bytes[0..64]), fq2(&bytes[64..128]))
+}
+
+#[test]
+fn test_decode_inner_fixtures() {
+    assert_eq!(INNER_PROOF.len(), 164);
+    assert_eq!(INNER_VK.len(), 364);
+    assert_eq!(INNER_WITNESS.len(), 44);
+
+    let proof = GnarkProof::decode(Curve::Bn254, INNER_PROOF).unwrap();
+    assert!(proof.commitments.is_empty());
+    assert_eq!(proof.commitment_pok, vec![0; 64]);
+
+    let vk = GnarkVerifyingKey::decode(Curve::Bn254, INNER_VK).unwrap();
+    assert_eq!(vk.n_public(), 1);
+    assert!(vk.public_and_commitment_committed.is_empty());
+
+    let witness = GnarkWitness::decode(Curve::Bn254, INNER_WITNESS).unwrap();
+    assert_eq!((witness.n_public, witness.n_secret), (1, 0));
+    let mut fifteen = [0u8; 32];
+    fifteen[31] = 15;
+    assert_eq!(witness.public(), &[fifteen]);
+
+    let proof = proof.to_contract_bytes().unwrap();
+    assert_eq!(proof.len(), 64 + 128 + 64);
+    let vk = vk.to_contract_bytes().unwrap();
+    assert_eq!(vk.len(), 64 + 3 * 128 + 2 * 64);
+
+    // e(a, b) == e(alpha, beta) * e(acc, gamma) * e(c, delta)
+    let input = Fr::from_be_bytes_mod_order(&witness.public()[0]);
+    let acc = (G1Projective::from(g1(&vk[448..512])) + g1(&vk[512..576]) * input).into_affine();
+    let lhs = Bn254::pairing(g1(&proof[0..64]), g2(&proof[64..192]));
+    let rhs = Bn254::multi_pairing(
+        [g1(&vk[0..64]), acc, g1(&proof[192..256])],
+        [g2(&vk[64..192]), g2(&vk[192..320]), g2(&vk[320..448])],
+    );
+    assert_eq!(lhs, rhs);
+}
+
+#[test]
+fn test_rejects_malformed_encodings() {
+    assert_eq!(
+        GnarkProof::decode(Curve::Bn254, &INNER_PROOF[..163]),
+        Err(Error::UnexpectedEof)
+    );
+    assert_eq!(
+        GnarkWitness::decode(Curve::Bn254, &[INNER_WITNESS, &[0]].concat()),
+        Err(Error::TrailingBytes(1))
+    );
+
+    // Flip a bit of Ar's x coordinate until it no longer lands on the curve.
+    let mut bad = INNER_PROOF.to_vec();
+    bad[31] ^= 1;
+    let mut x = 31;
+    while GnarkProof::decode(Curve::Bn254, &bad).is_ok() {
+        x -= 1;
+        bad[x] ^= 1;
+    }
+    assert_eq!(
+        GnarkProof::decode(Curve::Bn254, &bad),
+        Err(Error::InvalidG1)
+    );
+
+    let mut witness = INNER_WITNESS.to_vec();
+    witness[12..44].fill(0xff);
+    assert_eq!(
+        GnarkWitness::decode(Curve::Bn254, &witness),
+        Err(Error::OutOfRange)
+    );
+}
+
+#[test]
+fn test_commitment_layouts() {
+    // The fixtures with one commitment spliced in: the proof's `Ar` and
+    // `Krs` stand in for the commitment and its proof of knowledge, the
+    // key's `[beta]2` and `[gamma]2` for the Pedersen key, committing the
+    // first public input.
+    let proof = [
+        &INNER_PROOF[..128],
+        &1u32.to_be_bytes(),
+        &INNER_PROOF[..32],
+        &INNER_PROOF[96..128],
+    ]
+    .concat();
+    let proof = GnarkProof::decode(Curve::Bn254, &proof).unwrap();
+    assert_eq!(
+        proof.to_contract_bytes(),
+        Err(Error::UnsupportedCommitments)
+    );
+    let bytes = proof.to_contract_bytes_with_commitments();
+    assert_eq!(bytes.len(), 256 + 4 + 2 * 64);
+    assert_eq!(&bytes[256..260], &1u32.to_be_bytes());
+    assert_eq!(&bytes[260..324], &bytes[0..64]);
+    assert_eq!(&bytes[324..388], &bytes[192..256]);
+
+    let vk = [
+        &INNER_VK[..INNER_VK.len() - 8],
+        &1u32.to_be_bytes(),
+        &1u32.to_be_bytes(),
+        &1u64.to_be_bytes(),
+        &1u32.to_be_bytes(),
+        &INNER_VK[64..192],
+    ]
+    .concat();
+    let vk = GnarkVerifyingKey::decode(Curve::Bn254, &vk).unwrap();
+    assert_eq!(vk.n_public(), 0);
+
+    // Two committed lists need a third point in K.
+    let short = [
+        &INNER_VK[..INNER_VK.len() - 8],
+        &2u32.to_be_bytes(),
+        &1u32.to_be_bytes(),
+        &1u64.to_be_bytes(),
+        &1u32.to_be_bytes(),
+        &1u64.to_be_bytes(),
+        &0u32.to_be_bytes(),
+    ]
+    .concat();
+    assert_eq!(
+        GnarkVerifyingKey::decode(Curve::Bn254, &short),
+        Err(Error::TooFewKeyPoints { k: 2, needed: 3 })
+    );
+    assert_eq!(vk.public_and_commitment_committed, vec![vec![1]]);
+    assert_eq!(vk.to_contract_bytes(), Err(Error::UnsupportedCommitments));
+    let plain = GnarkVerifyingKey::decode(Curve::Bn254, INNER_VK)
+        .unwrap()
+        .to_contract_bytes()
+        .unwrap();
+    let bytes = vk.to_contract_bytes_with_commitments().unwrap();
+    assert_eq!(&bytes[0..4], &1u32.to_be_bytes());
+    assert_eq!(&bytes[4..132], &plain[64..192]);
+    assert_eq!(&bytes[132..260], &plain[192..320]);
+    assert_eq!(&bytes[260..268], &[0, 0, 0, 1, 0, 0, 0, 1]);
+    assert_eq!(&bytes[268..], &plain[..]);
+}
@@ -0,0 +1,181 @@
+//! Decimal field elements and snarkjs point arrays to and from the
+//! big-endian uncompressed encodings the contract takes.
+
+use crate::Error;
+
+/// snarkjs G1 point: `[x, y, z]` in projective coordinates.
+pub type G1Json = [String; 3];
+/// snarkjs G2 point: `[[x_c0, x_c1], [y_c0, y_c1], [z_c0, z_c1]]`.
+pub type G2Json = [[String; 2]; 3];
+
+/// BLS12-381 base field modulus p, big-endian.
+const FP_MODULUS: [u8; 48] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// BLS12-381 scalar field modulus r, big-endian.
+const FR_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+const FLAG_INFINITY: u8 = 0x40;
+
+/// Parses a decimal string into `N` big-endian bytes.
+fn decimal_to_be<const N: usize>(s: &str) -> Result<[u8; N], Error> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidNumber(s.to_string()));
+    }
+    let mut out = [0u8; N];
+    for digit in s.bytes().map(|c| c - b'0') {
+        let mut carry = digit as u16;
+        for byte in out.iter_mut().rev() {
+            let v = *byte as u16 * 10 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(Error::OutOfRange(s.to_string()));
+        }
+    }
+    Ok(out)
+}
+
+/// Formats big-endian bytes as a decimal string.
+fn be_to_decimal(bytes: &[u8]) -> String {
+    let mut n = bytes.to_vec();
+    let mut digits = Vec::new();
+    while n.iter().any(|b| *b != 0) {
+        let mut rem = 0u16;
+        for byte in n.iter_mut() {
+            let v = (rem << 8) | *byte as u16;
+            *byte = (v / 10) as u8;
+            rem = v % 10;
+        }
+        digits.push(b'0' + rem as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+fn fp(s: &str) -> Result<[u8; 48], Error> {
+    let v = decimal_to_be::<48>(s)?;
+    if v >= FP_MODULUS {
+        return Err(Error::OutOfRange(s.to_string()));
+    }
+    Ok(v)
+}
+
+/// Parses a public signal, which must be a canonical scalar field element.
+pub fn fr(s: &str) -> Result<[u8; 32], Error> {
+    let v = decimal_to_be::<32>(s)?;
+    if v >= FR_MODULUS {
+        return Err(Error::OutOfRange(s.to_string()));
+    }
+    Ok(v)
+}
+
+pub fn fr_to_decimal(bytes: &[u8; 32]) -> String {
+    be_to_decimal(bytes)
+}
+
+/// Whether a projective `z` marks an affine point (`Some(true)`) or the
+/// point at infinity (`Some(false)`).
+fn is_affine(z: &[&str]) -> Option<bool> {
+    match z {
+        ["1"] | ["1", "0"] => Some(true),
+        ["0"] | ["0", "0"] => Some(false),
+        _ => None,
+    }
+}
+
+pub fn g1_to_bytes(p: &G1Json) -> Result<[u8; 96], Error> {
+    let mut out = [0u8; 96];
+    match is_affine(&[&p[2]]) {
+        Some(true) => {
+            out[0..48].copy_from_slice(&fp(&p[0])?);
+            out[48..96].copy_from_slice(&fp(&p[1])?);
+        }
+        Some(false) => out[0] = FLAG_INFINITY,
+        None => return Err(Error::NotAffine),
+    }
+    Ok(out)
+}
+
+/// Writes `c1 || c0` for each coordinate, the order the contract expects.
+pub fn g2_to_bytes(p: &G2Json) -> Result<[u8; 192], Error> {
+    let mut out = [0u8; 192];
+    match is_affine(&[&p[2][0], &p[2][1]]) {
+        Some(true) => {
+            out[0..48].copy_from_slice(&fp(&p[0][1])?);
+            out[48..96].copy_from_slice(&fp(&p[0][0])?);
+            out[96..144].copy_from_slice(&fp(&p[1][1])?);
+            out[144..192].copy_from_slice(&fp(&p[1][0])?);
+        }
+        Some(false) => out[0] = FLAG_INFINITY,
+        None => return Err(Error::NotAffine),
+    }
+    Ok(out)
+}
+
+pub fn g1_from_bytes(bytes: &[u8]) -> G1Json {
+    if bytes[0] & FLAG_INFINITY != 0 {
+        return ["0".into(), "1".into(), "0".into()];
+    }
+    [
+        be_to_decimal(&bytes[0..48]),
+        be_to_decimal(&bytes[48..96]),
+        "1".into(),
+    ]
+}
+
+pub fn g2_from_bytes(bytes: &[u8]) -> G2Json {
+    if bytes[0] & FLAG_INFINITY != 0 {
+        return [
+            ["0".into(), "0".into()],
+            ["1".into(), "0".into()],
+            ["0".into(), "0".into()],
+        ];
+    }
+    [
+        [be_to_decimal(&bytes[48..96]), be_to_decimal(&bytes[0..48])],
+        [
+            be_to_decimal(&bytes[144..192]),
+            be_to_decimal(&bytes[96..144]),
+        ],
+        ["1".into(), "0".into()],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_round_trip() {
+        for s in [
+            "0",
+            "1",
+            "255",
+            "256",
+            "65535",
+            "123456789012345678901234567890",
+        ] {
+            assert_eq!(be_to_decimal(&decimal_to_be::<32>(s).unwrap()), s);
+        }
+        assert!(decimal_to_be::<1>("256").is_err());
+        assert!(decimal_to_be::<32>("").is_err());
+        assert!(decimal_to_be::<32>("-1").is_err());
+    }
+
+    #[test]
+    fn test_modulus_is_rejected() {
+        assert!(fr(&be_to_decimal(&FR_MODULUS)).is_err());
+        assert!(fp(&be_to_decimal(&FP_MODULUS)).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported protocol `{0}`, expected `groth16`")]
+    UnsupportedProtocol(String),
+    #[error("unsupported curve `{0}`, expected `bls12381`")]
+    UnsupportedCurve(String),
+    #[error("`{0}` is not a decimal integer")]
+    InvalidNumber(String),
+    #[error("`{0}` is not below the field modulus")]
+    OutOfRange(String),
+    #[error("point is neither affine nor the point at infinity")]
+    NotAffine,
+    #[error("IC has {ic_len} points, expected nPublic + 1 = {}", n_public + 1)]
+    IcLengthMismatch { n_public: u32, ic_len: usize },
+    #[error("invalid encoding length {0}")]
+    InvalidLength(usize),
+}
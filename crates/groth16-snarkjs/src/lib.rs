@@ -0,0 +1,173 @@
+//! Converts snarkjs Groth16 artifacts over BLS12-381 (`proof.json`,
+//! `verification_key.json` and `public.json`) into the byte layouts the
+//! `groth16` contract takes, and back.
+
+mod encoding;
+mod error;
+
+pub use encoding::{G1Json, G2Json};
+pub use error::Error;
+
+use serde::{Deserialize, Serialize};
+use soroban_sdk::{Bytes, BytesN, Env};
+
+const PROTOCOL: &str = "groth16";
+const CURVE: &str = "bls12381";
+
+fn check_header(protocol: &str, curve: &str) -> Result<(), Error> {
+    if protocol != PROTOCOL {
+        return Err(Error::UnsupportedProtocol(protocol.to_string()));
+    }
+    if curve != CURVE {
+        return Err(Error::UnsupportedCurve(curve.to_string()));
+    }
+    Ok(())
+}
+
+/// `proof.json` as written by `snarkjs groth16 prove`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnarkjsProof {
+    pub pi_a: G1Json,
+    pub pi_b: G2Json,
+    pub pi_c: G1Json,
+    pub protocol: String,
+    pub curve: String,
+}
+
+impl SnarkjsProof {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// The `a || b || c` uncompressed layout of the contract's `proof`.
+    pub fn to_bytes(&self) -> Result<[u8; 384], Error> {
+        check_header(&self.protocol, &self.curve)?;
+        let mut out = [0u8; 384];
+        out[0..96].copy_from_slice(&encoding::g1_to_bytes(&self.pi_a)?);
+        out[96..288].copy_from_slice(&encoding::g2_to_bytes(&self.pi_b)?);
+        out[288..384].copy_from_slice(&encoding::g1_to_bytes(&self.pi_c)?);
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8; 384]) -> Self {
+        SnarkjsProof {
+            pi_a: encoding::g1_from_bytes(&bytes[0..96]),
+            pi_b: encoding::g2_from_bytes(&bytes[96..288]),
+            pi_c: encoding::g1_from_bytes(&bytes[288..384]),
+            protocol: PROTOCOL.into(),
+            curve: CURVE.into(),
+        }
+    }
+
+    pub fn to_soroban(&self, env: &Env) -> Result<BytesN<384>, Error> {
+        Ok(BytesN::from_array(env, &self.to_bytes()?))
+    }
+}
+
+/// `verification_key.json` as written by `snarkjs zkey export
+/// verificationkey`. `vk_alphabeta_12` is not needed and is ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnarkjsVerificationKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: u32,
+    pub vk_alpha_1: G1Json,
+    pub vk_beta_2: G2Json,
+    pub vk_gamma_2: G2Json,
+    pub vk_delta_2: G2Json,
+    #[serde(rename = "IC")]
+    pub ic: Vec<G1Json>,
+}
+
+impl SnarkjsVerificationKey {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// The uncompressed `alpha || beta || gamma || delta || IC` layout of the
+    /// contract's `vk`, 672 + 96 * (nPublic + 1) bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        check_header(&self.protocol, &self.curve)?;
+        if self.ic.len() != self.n_public as usize + 1 {
+            return Err(Error::IcLengthMismatch {
+                n_public: self.n_public,
+                ic_len: self.ic.len(),
+            });
+        }
+        let mut out = Vec::with_capacity(672 + 96 * self.ic.len());
+        out.extend_from_slice(&encoding::g1_to_bytes(&self.vk_alpha_1)?);
+        out.extend_from_slice(&encoding::g2_to_bytes(&self.vk_beta_2)?);
+        out.extend_from_slice(&encoding::g2_to_bytes(&self.vk_gamma_2)?);
+        out.extend_from_slice(&encoding::g2_to_bytes(&self.vk_delta_2)?);
+        for p in &self.ic {
+            out.extend_from_slice(&encoding::g1_to_bytes(p)?);
+        }
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 672 + 96 || !(bytes.len() - 672).is_multiple_of(96) {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        let ic: Vec<G1Json> = bytes[672..]
+            .chunks(96)
+            .map(encoding::g1_from_bytes)
+            .collect();
+        Ok(SnarkjsVerificationKey {
+            protocol: PROTOCOL.into(),
+            curve: CURVE.into(),
+            n_public: ic.len() as u32 - 1,
+            vk_alpha_1: encoding::g1_from_bytes(&bytes[0..96]),
+            vk_beta_2: encoding::g2_from_bytes(&bytes[96..288]),
+            vk_gamma_2: encoding::g2_from_bytes(&bytes[288..480]),
+            vk_delta_2: encoding::g2_from_bytes(&bytes[480..672]),
+            ic,
+        })
+    }
+
+    pub fn to_soroban(&self, env: &Env) -> Result<Bytes, Error> {
+        Ok(Bytes::from_slice(env, &self.to_bytes()?))
+    }
+}
+
+/// `public.json`: the public signals as decimal strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SnarkjsPublicSignals(pub Vec<String>);
+
+impl SnarkjsPublicSignals {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Big-endian 32-byte scalars, each checked to be below r as the
+    /// contract requires.
+    pub fn to_bytes(&self) -> Result<Vec<[u8; 32]>, Error> {
+        self.0.iter().map(|s| encoding::fr(s)).collect()
+    }
+
+    pub fn from_bytes(signals: &[[u8; 32]]) -> Self {
+        SnarkjsPublicSignals(signals.iter().map(encoding::fr_to_decimal).collect())
+    }
+
+    pub fn to_soroban(&self, env: &Env) -> Result<soroban_sdk::Vec<BytesN<32>>, Error> {
+        let mut out = soroban_sdk::Vec::new(env);
+        for signal in self.to_bytes()? {
+            out.push_back(BytesN::from_array(env, &signal));
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,74 @@
+use groth16::{Groth16Contract, Groth16ContractClient};
+use groth16_snarkjs::{Error, SnarkjsProof, SnarkjsPublicSignals, SnarkjsVerificationKey};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+const PROOF_JSON: &str = include_str!("fixtures/proof.json");
+const VK_JSON: &str = include_str!("fixtures/verification_key.json");
+const PUBLIC_JSON: &str = include_str!("fixtures/public.json");
+
+#[test]
+fn test_fixtures_verify_on_contract() {
+    let env = Env::default();
+    let contract_id = env.register(Groth16Contract, (Address::generate(&env), 0u32));
+    let client = Groth16ContractClient::new(&env, &contract_id);
+
+    let proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+    let vk = SnarkjsVerificationKey::from_json(VK_JSON).unwrap();
+    let public = SnarkjsPublicSignals::from_json(PUBLIC_JSON).unwrap();
+
+    assert!(client
+        .try_verify(
+            &proof.to_soroban(&env).unwrap(),
+            &vk.to_soroban(&env).unwrap(),
+            &public.to_soroban(&env).unwrap(),
+        )
+        .is_ok());
+}
+
+#[test]
+fn test_round_trip() {
+    let proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+    let bytes = proof.to_bytes().unwrap();
+    assert_eq!(SnarkjsProof::from_bytes(&bytes), proof);
+    assert_eq!(SnarkjsProof::from_json(&proof.to_json()).unwrap(), proof);
+
+    let vk = SnarkjsVerificationKey::from_json(VK_JSON).unwrap();
+    let bytes = vk.to_bytes().unwrap();
+    assert_eq!(bytes.len(), 672 + 96 * 2);
+    assert_eq!(SnarkjsVerificationKey::from_bytes(&bytes).unwrap(), vk);
+    assert_eq!(
+        SnarkjsVerificationKey::from_json(&vk.to_json()).unwrap(),
+        vk
+    );
+
+    let public = SnarkjsPublicSignals::from_json(PUBLIC_JSON).unwrap();
+    let bytes = public.to_bytes().unwrap();
+    assert_eq!(SnarkjsPublicSignals::from_bytes(&bytes), public);
+    assert_eq!(
+        SnarkjsPublicSignals::from_json(&public.to_json()).unwrap(),
+        public
+    );
+}
+
+#[test]
+fn test_rejects_unsupported_artifacts() {
+    let mut proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+    proof.curve = "bn128".into();
+    assert!(matches!(proof.to_bytes(), Err(Error::UnsupportedCurve(_))));
+
+    let mut vk = SnarkjsVerificationKey::from_json(VK_JSON).unwrap();
+    vk.n_public = 2;
+    assert!(matches!(
+        vk.to_bytes(),
+        Err(Error::IcLengthMismatch {
+            n_public: 2,
+            ic_len: 2
+        })
+    ));
+
+    // r itself is not a canonical public signal.
+    let public = SnarkjsPublicSignals(vec![
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513".into(),
+    ]);
+    assert!(matches!(public.to_bytes(), Err(Error::OutOfRange(_))));
+}
@@ -0,0 +1,150 @@
+//! Loads proofs, verifying keys and public inputs from each supported
+//! toolchain into the byte layouts the contract's `verify` takes, or
+//! `verify_bn254`'s for BN254.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use clap::ValueEnum;
+use groth16_gnark::{Curve as GnarkCurve, GnarkProof, GnarkVerifyingKey, GnarkWitness};
+use groth16_snarkjs::{SnarkjsProof, SnarkjsPublicSignals, SnarkjsVerificationKey};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// `proof.json`, `verification_key.json` and `public.json`.
+    Snarkjs,
+    /// `CanonicalSerialize` output of `Proof`, `VerifyingKey` and
+    /// `Vec<Fr>`, compressed or not.
+    Arkworks,
+    /// gnark's `WriteTo` output of the proof and key, and the public
+    /// witness's `MarshalBinary` output.
+    Gnark,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Curve {
+    /// Arguments for `verify`.
+    #[value(name = "bls12-381")]
+    Bls12_381,
+    /// Arguments for `verify_bn254`. Only gnark artifacts are read.
+    Bn254,
+}
+
+impl Curve {
+    /// The contract function that verifies proofs on this curve.
+    pub fn entrypoint(self) -> &'static str {
+        match self {
+            Curve::Bls12_381 => "verify",
+            Curve::Bn254 => "verify_bn254",
+        }
+    }
+
+    fn gnark(self) -> GnarkCurve {
+        match self {
+            Curve::Bls12_381 => GnarkCurve::Bls12_381,
+            Curve::Bn254 => GnarkCurve::Bn254,
+        }
+    }
+}
+
+/// The snarkjs and arkworks readers only know BLS12-381.
+fn check_curve(format: Format, curve: Curve) -> Result<()> {
+    if curve == Curve::Bn254 && format != Format::Gnark {
+        bail!("only gnark artifacts can be read on BN254");
+    }
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("reading {}", path.display()))
+}
+
+fn read_to_string(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Deserializes the whole of `bytes`, accepting either ark encoding.
+fn ark_deserialize<T: CanonicalDeserialize>(bytes: &[u8], what: &str) -> Result<T> {
+    for compressed in [false, true] {
+        let mut reader = bytes;
+        let value = if compressed {
+            T::deserialize_compressed(&mut reader)
+        } else {
+            T::deserialize_uncompressed(&mut reader)
+        };
+        if let Ok(value) = value {
+            if reader.is_empty() {
+                return Ok(value);
+            }
+        }
+    }
+    bail!("not a valid arkworks {what}")
+}
+
+fn ark_uncompressed<T: CanonicalSerialize>(value: &T, out: &mut Vec<u8>) {
+    value.serialize_uncompressed(out).unwrap();
+}
+
+/// The `a || b || c` proof: 384 bytes on BLS12-381, 256 on BN254.
+pub fn load_proof(format: Format, curve: Curve, path: &Path) -> Result<Vec<u8>> {
+    check_curve(format, curve)?;
+    match format {
+        Format::Snarkjs => Ok(SnarkjsProof::from_json(&read_to_string(path)?)?
+            .to_bytes()?
+            .to_vec()),
+        Format::Arkworks => {
+            let proof: Proof<Bls12_381> = ark_deserialize(&read(path)?, "proof")?;
+            let mut out = Vec::with_capacity(384);
+            ark_uncompressed(&proof, &mut out);
+            Ok(out)
+        }
+        Format::Gnark => Ok(GnarkProof::decode(curve.gnark(), &read(path)?)?.to_contract_bytes()?),
+    }
+}
+
+/// The uncompressed key layout, without arkworks' length prefix.
+pub fn load_vk(format: Format, curve: Curve, path: &Path) -> Result<Vec<u8>> {
+    check_curve(format, curve)?;
+    match format {
+        Format::Snarkjs => {
+            Ok(SnarkjsVerificationKey::from_json(&read_to_string(path)?)?.to_bytes()?)
+        }
+        Format::Arkworks => {
+            let vk: VerifyingKey<Bls12_381> = ark_deserialize(&read(path)?, "verifying key")?;
+            let mut out = Vec::with_capacity(672 + 96 * vk.gamma_abc_g1.len());
+            ark_uncompressed(&vk.alpha_g1, &mut out);
+            ark_uncompressed(&vk.beta_g2, &mut out);
+            ark_uncompressed(&vk.gamma_g2, &mut out);
+            ark_uncompressed(&vk.delta_g2, &mut out);
+            for p in &vk.gamma_abc_g1 {
+                ark_uncompressed(p, &mut out);
+            }
+            Ok(out)
+        }
+        Format::Gnark => {
+            Ok(GnarkVerifyingKey::decode(curve.gnark(), &read(path)?)?.to_contract_bytes()?)
+        }
+    }
+}
+
+/// Big-endian 32-byte scalars.
+pub fn load_public_inputs(format: Format, curve: Curve, path: &Path) -> Result<Vec<[u8; 32]>> {
+    check_curve(format, curve)?;
+    match format {
+        Format::Snarkjs => Ok(SnarkjsPublicSignals::from_json(&read_to_string(path)?)?.to_bytes()?),
+        Format::Arkworks => {
+            let inputs: Vec<Fr> = ark_deserialize(&read(path)?, "public input vector")?;
+            Ok(inputs
+                .iter()
+                .map(|fr| fr.into_bigint().to_bytes_be().try_into().unwrap())
+                .collect())
+        }
+        Format::Gnark => Ok(GnarkWitness::decode(curve.gnark(), &read(path)?)?
+            .public()
+            .to_vec()),
+    }
+}
@@ -0,0 +1,136 @@
+//! Turns Groth16 artifacts into arguments for the contract's `verify`, or
+//! `verify_bn254` for BN254 artifacts.
+
+mod artifacts;
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use artifacts::{Curve, Format};
+use clap::{Args, Parser, Subcommand};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{Limits, ScBytes, ScVal, ScVec, WriteXdr};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the `verify` or `verify_bn254` arguments as hex and as base64
+    /// XDR `ScVal`s.
+    Convert(Inputs),
+    /// Print the ID `register_vk` assigns to the key: sha256 of its
+    /// uncompressed encoding. `register_vk` only takes BLS12-381 keys.
+    VkId {
+        #[arg(long, value_enum)]
+        format: Format,
+        #[arg(long)]
+        vk: PathBuf,
+    },
+    /// Write the arguments of `stellar contract invoke ... -- verify`, or
+    /// `verify_bn254`, to a file, ready to paste after the `--`.
+    InvokeArgs {
+        #[command(flatten)]
+        inputs: Inputs,
+        #[arg(long, short)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct Inputs {
+    #[arg(long, value_enum)]
+    format: Format,
+    #[arg(long, value_enum, default_value = "bls12-381")]
+    curve: Curve,
+    #[arg(long)]
+    proof: PathBuf,
+    #[arg(long)]
+    vk: PathBuf,
+    #[arg(long)]
+    public: PathBuf,
+}
+
+/// The three arguments of `curve`'s verify function in contract layout.
+struct VerifyArgs {
+    curve: Curve,
+    proof: Vec<u8>,
+    vk: Vec<u8>,
+    public_inputs: Vec<[u8; 32]>,
+}
+
+impl Inputs {
+    fn load(&self) -> Result<VerifyArgs> {
+        Ok(VerifyArgs {
+            curve: self.curve,
+            proof: artifacts::load_proof(self.format, self.curve, &self.proof)?,
+            vk: artifacts::load_vk(self.format, self.curve, &self.vk)?,
+            public_inputs: artifacts::load_public_inputs(self.format, self.curve, &self.public)?,
+        })
+    }
+}
+
+fn bytes_val(bytes: &[u8]) -> ScVal {
+    ScVal::Bytes(ScBytes(bytes.to_vec().try_into().unwrap()))
+}
+
+fn xdr_base64(val: &ScVal) -> String {
+    val.to_xdr_base64(Limits::none()).unwrap()
+}
+
+fn public_inputs_val(inputs: &[[u8; 32]]) -> ScVal {
+    let vals: Vec<ScVal> = inputs.iter().map(|i| bytes_val(i)).collect();
+    ScVal::Vec(Some(ScVec(vals.try_into().unwrap())))
+}
+
+fn public_inputs_json(inputs: &[[u8; 32]]) -> String {
+    let hex: Vec<String> = inputs
+        .iter()
+        .map(|i| format!("\"{}\"", hex::encode(i)))
+        .collect();
+    format!("[{}]", hex.join(","))
+}
+
+fn convert(args: &VerifyArgs) {
+    println!("proof (hex): {}", hex::encode(&args.proof));
+    println!("vk (hex): {}", hex::encode(&args.vk));
+    println!(
+        "public_inputs (hex): {}",
+        public_inputs_json(&args.public_inputs)
+    );
+    println!("proof (xdr): {}", xdr_base64(&bytes_val(&args.proof)));
+    println!("vk (xdr): {}", xdr_base64(&bytes_val(&args.vk)));
+    println!(
+        "public_inputs (xdr): {}",
+        xdr_base64(&public_inputs_val(&args.public_inputs))
+    );
+}
+
+fn invoke_args(args: &VerifyArgs) -> String {
+    format!(
+        "{} \\\n  --proof {} \\\n  --vk {} \\\n  --public_inputs '{}'\n",
+        args.curve.entrypoint(),
+        hex::encode(&args.proof),
+        hex::encode(&args.vk),
+        public_inputs_json(&args.public_inputs),
+    )
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Convert(inputs) => convert(&inputs.load()?),
+        Command::VkId { format, vk } => {
+            let vk = artifacts::load_vk(format, Curve::Bls12_381, &vk)?;
+            println!("{}", hex::encode(Sha256::digest(&vk)));
+        }
+        Command::InvokeArgs { inputs, out } => {
+            fs::write(&out, invoke_args(&inputs.load()?))
+                .with_context(|| format!("writing {}", out.display()))?;
+        }
+    }
+    Ok(())
+}
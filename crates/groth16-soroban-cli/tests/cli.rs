@@ -0,0 +1,324 @@
+use std::{fs, path::PathBuf, process::Command};
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use groth16::{Groth16Contract, Groth16ContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env};
+
+const CLI: &str = env!("CARGO_BIN_EXE_groth16-soroban-cli");
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../groth16-snarkjs/tests/fixtures")
+        .join(name)
+}
+
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("groth16-soroban-cli-{test}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(CLI).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn snarkjs_args<'a>(proof: &'a str, vk: &'a str, public: &'a str) -> Vec<&'a str> {
+    vec![
+        "--format", "snarkjs", "--proof", proof, "--vk", vk, "--public", public,
+    ]
+}
+
+#[test]
+fn test_vk_id_matches_register_vk() {
+    let vk_path = fixture("verification_key.json");
+    let id = run(&[
+        "vk-id",
+        "--format",
+        "snarkjs",
+        "--vk",
+        vk_path.to_str().unwrap(),
+    ]);
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Groth16Contract, (Address::generate(&env), 0u32));
+    let client = Groth16ContractClient::new(&env, &contract_id);
+    let vk =
+        groth16_snarkjs::SnarkjsVerificationKey::from_json(&fs::read_to_string(&vk_path).unwrap())
+            .unwrap();
+    let registered = client.register_vk(&Bytes::from_slice(&env, &vk.to_bytes().unwrap()), &1, &1);
+
+    assert_eq!(id.trim(), hex::encode(registered.to_array()));
+}
+
+fn fixture_paths() -> [String; 3] {
+    ["proof.json", "verification_key.json", "public.json"]
+        .map(|name| fixture(name).to_str().unwrap().to_string())
+}
+
+/// The snarkjs fixtures as arkworks values.
+fn ark_fixtures() -> (Proof<Bls12_381>, VerifyingKey<Bls12_381>, Vec<Fr>) {
+    let [proof, vk, public] = fixture_paths();
+    let bytes = groth16_snarkjs::SnarkjsProof::from_json(&fs::read_to_string(proof).unwrap())
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let ark_proof = Proof::<Bls12_381> {
+        a: G1Affine::deserialize_uncompressed(&bytes[0..96]).unwrap(),
+        b: G2Affine::deserialize_uncompressed(&bytes[96..288]).unwrap(),
+        c: G1Affine::deserialize_uncompressed(&bytes[288..384]).unwrap(),
+    };
+    let bytes =
+        groth16_snarkjs::SnarkjsVerificationKey::from_json(&fs::read_to_string(vk).unwrap())
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+    let ark_vk = VerifyingKey::<Bls12_381> {
+        alpha_g1: G1Affine::deserialize_uncompressed(&bytes[0..96]).unwrap(),
+        beta_g2: G2Affine::deserialize_uncompressed(&bytes[96..288]).unwrap(),
+        gamma_g2: G2Affine::deserialize_uncompressed(&bytes[288..480]).unwrap(),
+        delta_g2: G2Affine::deserialize_uncompressed(&bytes[480..672]).unwrap(),
+        gamma_abc_g1: bytes[672..]
+            .chunks(96)
+            .map(|p| G1Affine::deserialize_uncompressed(p).unwrap())
+            .collect(),
+    };
+    let ark_public =
+        groth16_snarkjs::SnarkjsPublicSignals::from_json(&fs::read_to_string(public).unwrap())
+            .unwrap()
+            .to_bytes()
+            .unwrap()
+            .iter()
+            .map(|s| Fr::from_be_bytes_mod_order(s))
+            .collect();
+    (ark_proof, ark_vk, ark_public)
+}
+
+fn compressed<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.serialize_compressed(&mut out).unwrap();
+    out
+}
+
+/// Runs `convert` over files written to a scratch directory.
+fn convert_files(test: &str, format: &str, files: [Vec<u8>; 3]) -> String {
+    let dir = scratch_dir(test);
+    let [proof, vk, public] = ["proof.bin", "vk.bin", "public.bin"].map(|name| dir.join(name));
+    for (path, bytes) in [&proof, &vk, &public].into_iter().zip(files) {
+        fs::write(path, bytes).unwrap();
+    }
+    let output = run(&[
+        "convert",
+        "--format",
+        format,
+        "--proof",
+        proof.to_str().unwrap(),
+        "--vk",
+        vk.to_str().unwrap(),
+        "--public",
+        public.to_str().unwrap(),
+    ]);
+    fs::remove_dir_all(dir).unwrap();
+    output
+}
+
+fn snarkjs_convert() -> String {
+    let [proof, vk, public] = fixture_paths();
+    let mut args = vec!["convert"];
+    args.extend(snarkjs_args(&proof, &vk, &public));
+    run(&args)
+}
+
+#[test]
+fn test_arkworks_and_snarkjs_agree() {
+    let (proof, vk, public) = ark_fixtures();
+    let from_arkworks = convert_files(
+        "ark",
+        "arkworks",
+        [compressed(&proof), compressed(&vk), compressed(&public)],
+    );
+    assert_eq!(from_arkworks, snarkjs_convert());
+}
+
+#[test]
+fn test_gnark_and_snarkjs_agree() {
+    let (proof, vk, public) = ark_fixtures();
+    // gnark's BLS12-381 compressed points use the same ZCash flags.
+    let infinity = [&[0xc0][..], &[0; 47]].concat();
+
+    let gnark_proof = [
+        compressed(&proof.a),
+        compressed(&proof.b),
+        compressed(&proof.c),
+        0u32.to_be_bytes().to_vec(),
+        infinity,
+    ]
+    .concat();
+    let mut gnark_vk = [
+        compressed(&vk.alpha_g1),
+        compressed(&vk.alpha_g1), // [beta]1, unused by the contract
+        compressed(&vk.beta_g2),
+        compressed(&vk.gamma_g2),
+        compressed(&vk.alpha_g1), // [delta]1, unused by the contract
+        compressed(&vk.delta_g2),
+        (vk.gamma_abc_g1.len() as u32).to_be_bytes().to_vec(),
+    ]
+    .concat();
+    for p in &vk.gamma_abc_g1 {
+        gnark_vk.extend(compressed(p));
+    }
+    gnark_vk.extend([0u8; 8]);
+    let mut gnark_witness = [1u32, 0, 1].map(u32::to_be_bytes).concat();
+    gnark_witness.extend(public[0].into_bigint().to_bytes_be());
+
+    let from_gnark = convert_files("gnark", "gnark", [gnark_proof, gnark_vk, gnark_witness]);
+    assert_eq!(from_gnark, snarkjs_convert());
+}
+
+#[test]
+fn test_invoke_args_file() {
+    let (proof, vk, public) = (
+        fixture("proof.json"),
+        fixture("verification_key.json"),
+        fixture("public.json"),
+    );
+    let dir = scratch_dir("invoke");
+    let out = dir.join("args.txt");
+    let mut args = vec!["invoke-args", "--out", out.to_str().unwrap()];
+    args.extend(snarkjs_args(
+        proof.to_str().unwrap(),
+        vk.to_str().unwrap(),
+        public.to_str().unwrap(),
+    ));
+    run(&args);
+
+    let written = fs::read_to_string(&out).unwrap();
+    let proof_hex = hex::encode(
+        groth16_snarkjs::SnarkjsProof::from_json(&fs::read_to_string(&proof).unwrap())
+            .unwrap()
+            .to_bytes()
+            .unwrap(),
+    );
+    assert!(written.starts_with("verify \\\n"));
+    assert!(written.contains(&format!("--proof {proof_hex} \\\n")));
+    assert!(written.contains("--public_inputs '[\""));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+fn inner_fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(name)
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+/// The value after `flag` on an `invoke-args` line.
+fn invoke_arg<'a>(written: &'a str, flag: &str) -> &'a str {
+    let line = written
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(flag))
+        .unwrap();
+    line.trim().trim_end_matches('\\').trim().trim_matches('\'')
+}
+
+#[test]
+fn test_gnark_bn254_invoke_args_verify() {
+    let dir = scratch_dir("bn254");
+    let out = dir.join("args.txt");
+    let [proof, vk, public] = ["inner_proof", "inner_vk", "inner_witness"].map(inner_fixture);
+    run(&[
+        "invoke-args",
+        "--out",
+        out.to_str().unwrap(),
+        "--format",
+        "gnark",
+        "--curve",
+        "bn254",
+        "--proof",
+        &proof,
+        "--vk",
+        &vk,
+        "--public",
+        &public,
+    ]);
+    let written = fs::read_to_string(&out).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+    assert!(written.starts_with("verify_bn254 \\\n"));
+
+    let env = Env::default();
+    let contract_id = env.register(Groth16Contract, (Address::generate(&env), 0u32));
+    let client = Groth16ContractClient::new(&env, &contract_id);
+    let proof: [u8; 256] = hex::decode(invoke_arg(&written, "--proof"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let vk = hex::decode(invoke_arg(&written, "--vk")).unwrap();
+    let mut inputs = soroban_sdk::Vec::new(&env);
+    for input in invoke_arg(&written, "--public_inputs")
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+    {
+        let input: [u8; 32] = hex::decode(input.trim_matches('"'))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        inputs.push_back(BytesN::from_array(&env, &input));
+    }
+    client.verify_bn254(
+        &BytesN::from_array(&env, &proof),
+        &Bytes::from_slice(&env, &vk),
+        &inputs,
+    );
+}
+
+#[test]
+fn test_bn254_needs_gnark_artifacts() {
+    let [proof, vk, public] = fixture_paths();
+    let mut args = vec!["convert", "--curve", "bn254"];
+    args.extend(snarkjs_args(&proof, &vk, &public));
+    let output = Command::new(CLI).args(&args).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_vk_id_is_bls12_381_only() {
+    let inner_vk = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../inner_vk");
+    let output = Command::new(CLI)
+        .args(["vk-id", "--format", "gnark", "--curve", "bn254", "--vk"])
+        .arg(inner_vk)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_xdr_matches_the_sdk() {
+    use soroban_sdk::xdr::{Limits, ReadXdr, ScVal};
+
+    let output = snarkjs_convert();
+    let xdr = output
+        .lines()
+        .find_map(|line| line.strip_prefix("proof (xdr): "))
+        .unwrap();
+    let [proof, _, _] = fixture_paths();
+    let proof = groth16_snarkjs::SnarkjsProof::from_json(&fs::read_to_string(proof).unwrap())
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let ScVal::Bytes(bytes) = ScVal::from_xdr_base64(xdr, Limits::none()).unwrap() else {
+        panic!("not a bytes ScVal");
+    };
+    assert_eq!(bytes.as_slice(), &proof[..]);
+}
@@ -0,0 +1,8 @@
+module github.com/veil-protocol-privacy/groth16-soroban/fixtures/gnark-commitment
+
+go 1.22
+
+require (
+	github.com/consensys/gnark v0.11.0
+	github.com/consensys/gnark-crypto v0.14.0
+)
@@ -0,0 +1,88 @@
+// Writes a gnark BLS12-381 Groth16 proof of a circuit using the Commit API,
+// for the contract's verify_with_commitments tests. Run from this directory
+// with `go mod tidy && go run .`; the files land in the repository root next
+// to the inner_* fixtures.
+package main
+
+import (
+	"log"
+	"os"
+	"path/filepath"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark/backend/groth16"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+)
+
+// Circuit proves knowledge of Y with Y * Y == X, and commits to both the
+// public X and the secret Y so the commitment hash binds a public input.
+type Circuit struct {
+	X frontend.Variable `gnark:",public"`
+	Y frontend.Variable
+}
+
+func (c *Circuit) Define(api frontend.API) error {
+	commitment, err := api.(frontend.Committer).Commit(c.X, c.Y)
+	if err != nil {
+		return err
+	}
+	api.AssertIsDifferent(commitment, 0)
+	api.AssertIsEqual(api.Mul(c.Y, c.Y), c.X)
+	return nil
+}
+
+func write(name string, writeTo func(f *os.File) error) {
+	f, err := os.Create(filepath.Join("..", "..", name))
+	if err != nil {
+		log.Fatal(err)
+	}
+	defer f.Close()
+	if err := writeTo(f); err != nil {
+		log.Fatal(err)
+	}
+}
+
+func main() {
+	field := ecc.BLS12_381.ScalarField()
+	ccs, err := frontend.Compile(field, r1cs.NewBuilder, &Circuit{})
+	if err != nil {
+		log.Fatal(err)
+	}
+	pk, vk, err := groth16.Setup(ccs)
+	if err != nil {
+		log.Fatal(err)
+	}
+	witness, err := frontend.NewWitness(&Circuit{X: 49, Y: 7}, field)
+	if err != nil {
+		log.Fatal(err)
+	}
+	proof, err := groth16.Prove(ccs, pk, witness)
+	if err != nil {
+		log.Fatal(err)
+	}
+	public, err := witness.Public()
+	if err != nil {
+		log.Fatal(err)
+	}
+	if err := groth16.Verify(proof, vk, public); err != nil {
+		log.Fatal(err)
+	}
+
+	write("commit_proof", func(f *os.File) error {
+		_, err := proof.WriteTo(f)
+		return err
+	})
+	write("commit_vk", func(f *os.File) error {
+		_, err := vk.WriteTo(f)
+		return err
+	})
+	write("commit_witness", func(f *os.File) error {
+		bytes, err := public.MarshalBinary()
+		if err != nil {
+			return err
+		}
+		_, err = f.Write(bytes)
+		return err
+	})
+}